@@ -0,0 +1,637 @@
+// Command-line options of the binary.
+
+use std::path::PathBuf;
+
+use crate::constants::{WORD_LENGTH, MIN_WORD_LENGTH, MAX_WORD_LENGTH, MAX_GUESSES, GUESS_LIMIT, BOARD_COUNTS};
+use crate::err::AppError;
+use crate::puzzle::PuzzleId;
+use crate::utils;
+use crate::wordbank::WordList;
+
+// What the program is asked to do.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Command {
+    Play,
+    ShowHelp,
+    ShowVersion,
+    ShowStats,
+}
+
+// How the answer of the game is picked.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Selection {
+    Random,
+    Daily,
+    Seed(u64),
+    Puzzle(PuzzleId),
+}
+
+// What kind of game is played.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Mode {
+    Classic,
+    // The answer is only decided when the player forces it.
+    Absurdle,
+    // Several boards at once, each with its own answer.
+    MultiBoard(usize),
+    // The player thinks of a word and the program guesses it.
+    Reverse,
+}
+
+impl Mode {
+    pub fn parse(name: &str) -> Option<Mode> {
+        match name.to_lowercase().as_str() {
+            "classic" => Some(Mode::Classic),
+            "absurdle" => Some(Mode::Absurdle),
+            "reverse" => Some(Mode::Reverse),
+            "dordle" => Some(Mode::MultiBoard(2)),
+            "quordle" => Some(Mode::MultiBoard(4)),
+            "octordle" => Some(Mode::MultiBoard(8)),
+            _ => None,
+        }
+    }
+
+    pub fn get_names() -> Vec<&'static str> {
+        vec!["classic", "absurdle", "reverse", "dordle", "quordle", "octordle"]
+    }
+
+    // Dordle, Quordle and Octordle give one more guess for every extra board.
+    fn get_default_max_guesses(&self) -> Option<usize> {
+        match self {
+            Mode::Classic | Mode::Reverse => Some(MAX_GUESSES),
+            Mode::Absurdle => None,
+            Mode::MultiBoard(board_count) => Some(MAX_GUESSES + board_count - 1),
+        }
+    }
+
+    // Absurdle has no answer to pick, and in reverse mode the player picks it.
+    fn has_answer_to_select(&self) -> bool {
+        !matches!(self, Mode::Absurdle | Mode::Reverse)
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Options {
+    command: Command,
+    word_length: usize,
+    word_length_set: bool,
+    max_guesses: Option<usize>,
+    hard_mode: bool,
+    selection: Selection,
+    mode: Mode,
+    word_list: WordList,
+    color: bool,
+    data_dir: Option<PathBuf>,
+}
+
+impl Options {
+    pub fn get_command(&self) -> Command {
+        self.command
+    }
+
+    pub fn get_word_length(&self) -> usize {
+        self.word_length
+    }
+
+    // Whether the word length was asked for, rather than the default one.
+    pub fn is_word_length_set(&self) -> bool {
+        self.word_length_set
+    }
+
+    pub fn get_max_guesses(&self) -> Option<usize> {
+        self.max_guesses
+    }
+
+    pub fn is_hard_mode(&self) -> bool {
+        self.hard_mode
+    }
+
+    pub fn get_selection(&self) -> Selection {
+        self.selection
+    }
+
+    pub fn get_mode(&self) -> Mode {
+        self.mode
+    }
+
+    pub fn get_word_list(&self) -> &WordList {
+        &self.word_list
+    }
+
+    pub fn is_color_enabled(&self) -> bool {
+        self.color
+    }
+
+    pub fn get_data_dir(&self) -> Option<&PathBuf> {
+        self.data_dir.as_ref()
+    }
+
+    pub fn is_daily(&self) -> bool {
+        self.selection == Selection::Daily
+    }
+}
+
+impl Default for Options {
+    fn default() -> Options {
+        Options {
+            command: Command::Play,
+            word_length: WORD_LENGTH,
+            word_length_set: false,
+            max_guesses: Some(MAX_GUESSES),
+            hard_mode: false,
+            selection: Selection::Random,
+            mode: Mode::Classic,
+            word_list: WordList::Common,
+            color: true,
+            data_dir: None,
+        }
+    }
+}
+
+pub fn get_usage() -> String {
+    let boards = format!("{}, {} or {}", BOARD_COUNTS[0], BOARD_COUNTS[1], BOARD_COUNTS[2]);
+
+    format!(
+"Usage: wordle_clone [OPTIONS]
+
+Play Wordle in the terminal.
+
+Options:
+  -m, --mode <MODE>         Kind of game: {modes} [default: classic]
+      --absurdle            Same as --mode absurdle
+      --reverse             Same as --mode reverse
+  -b, --boards <N>          Play {boards} boards at once
+  -l, --length <N>          Word length, from {min} to {max} [default: {length}]
+  -g, --guesses <N>         Number of guesses, from 1 to {limit}, or 'unlimited'
+      --hard                Every revealed hint must be used in later guesses
+      --daily               Play the puzzle of the day
+      --seed <N>            Pick the answers from a seed
+      --puzzle <ID>         Play the answer of a puzzle ID, e.g. 5-70
+  -w, --word-list <NAME>    Words the answer is picked from: {lists} [default: common]
+      --answers <PATH>      Pick the answer from a file, one word per line, sorted
+      --allowed <PATH>      Only allow the guesses in a file, one word per line, sorted
+      --no-color            Print without colors
+      --data-dir <PATH>     Keep the scores, statistics and word packs in this directory
+                            instead of the XDG ones [env: {dir_var}]
+      --stats               Show the high scores and statistics, then exit
+  -h, --help                Show this help, then exit
+  -V, --version             Show the version, then exit",
+        modes = Mode::get_names().join(", "),
+        boards = boards,
+        min = MIN_WORD_LENGTH,
+        max = MAX_WORD_LENGTH,
+        length = WORD_LENGTH,
+        limit = GUESS_LIMIT,
+        dir_var = utils::DIR_ENV_VAR,
+        lists = WordList::get_names().join(", "),
+    )
+}
+
+// Parses the arguments passed to the program, excluding the program name.
+// Asking for the help or the version stops at that argument.
+pub fn parse_args<I: IntoIterator<Item = String>>(args: I) -> Result<Options, AppError> {
+    let mut options = Options::default();
+    let mut args = args.into_iter();
+
+    // The arguments that set each of these options, to report conflicts.
+    let mut length_arg: Option<String> = None;
+    let mut selection_arg: Option<String> = None;
+    let mut guesses_arg: Option<String> = None;
+    let mut mode_arg: Option<String> = None;
+    let mut word_list_arg: Option<String> = None;
+    let mut hard_arg: Option<String> = None;
+
+    while let Some(arg) = args.next() {
+        let mode = match arg.as_str() {
+            "-h" | "--help" => return Ok(Options { command: Command::ShowHelp, ..options }),
+            "-V" | "--version" => return Ok(Options { command: Command::ShowVersion, ..options }),
+            "--stats" => {
+                options.command = Command::ShowStats;
+                None
+            }
+            "-l" | "--length" => {
+                let value = next_value(&arg, &mut args)?;
+                options.word_length = parse_word_length(&value)?;
+                length_arg = Some(arg.clone());
+                None
+            }
+            "-g" | "--guesses" => {
+                let value = next_value(&arg, &mut args)?;
+                options.max_guesses = parse_max_guesses(&value)?;
+                guesses_arg = Some(arg.clone());
+                None
+            }
+            "--hard" => {
+                options.hard_mode = true;
+                hard_arg = Some(arg.clone());
+                None
+            }
+            "--no-color" => {
+                options.color = false;
+                None
+            }
+            "--data-dir" => {
+                options.data_dir = Some(PathBuf::from(next_value(&arg, &mut args)?));
+                None
+            }
+            "--daily" | "--seed" | "--puzzle" => {
+                check_conflict(&selection_arg, &arg)?;
+                options.selection = match arg.as_str() {
+                    "--daily" => Selection::Daily,
+                    "--seed" => {
+                        let value = next_value(&arg, &mut args)?;
+                        Selection::Seed(value.parse().map_err(|_| AppError::InvalidSeedErr(value))?)
+                    }
+                    _ => Selection::Puzzle(PuzzleId::parse(&next_value(&arg, &mut args)?)?),
+                };
+                selection_arg = Some(arg.clone());
+                None
+            }
+            "-w" | "--word-list" => {
+                let value = next_value(&arg, &mut args)?;
+                options.word_list = WordList::parse(&value).ok_or(AppError::InvalidWordListErr(value))?;
+                word_list_arg = Some(arg.clone());
+                None
+            }
+            "--answers" | "--allowed" => {
+                let path = PathBuf::from(next_value(&arg, &mut args)?);
+
+                if !matches!(options.word_list, WordList::Files { .. }) {
+                    check_conflict(&word_list_arg, &arg)?;
+                    options.word_list = WordList::Files { answers: None, allowed: None };
+                }
+
+                if let WordList::Files { answers, allowed } = &mut options.word_list {
+                    match arg.as_str() {
+                        "--answers" => *answers = Some(path),
+                        _ => *allowed = Some(path),
+                    }
+                }
+
+                word_list_arg = Some(arg.clone());
+                None
+            }
+            "-m" | "--mode" => {
+                let value = next_value(&arg, &mut args)?;
+                Some(Mode::parse(&value).ok_or(AppError::InvalidModeErr(value))?)
+            }
+            "--absurdle" => Some(Mode::Absurdle),
+            "--reverse" => Some(Mode::Reverse),
+            "-b" | "--boards" => {
+                let value = next_value(&arg, &mut args)?;
+                Some(Mode::MultiBoard(parse_board_count(&value)?))
+            }
+            _ => return Err(AppError::UnknownArgumentErr(arg)),
+        };
+
+        if let Some(mode) = mode {
+            check_conflict(&mode_arg, &arg)?;
+            options.mode = mode;
+            mode_arg = Some(arg);
+        }
+    }
+
+    // Modes without an answer to pick cannot be combined with the ways of picking one.
+    if !options.mode.has_answer_to_select() && selection_arg.is_some() {
+        check_conflict(&selection_arg, mode_arg.as_deref().unwrap_or_default())?;
+    }
+
+    // In reverse mode the game does the guessing, so there is nothing for hard mode to restrict.
+    if options.mode == Mode::Reverse {
+        check_conflict(&hard_arg, mode_arg.as_deref().unwrap_or_default())?;
+    }
+
+    // The daily puzzle and puzzle IDs are about a single word of the common list.
+    if let Selection::Daily | Selection::Puzzle(_) = options.selection {
+        if let Mode::MultiBoard(_) = options.mode {
+            check_conflict(&selection_arg, mode_arg.as_deref().unwrap_or_default())?;
+        }
+
+        if options.word_list != WordList::Common {
+            check_conflict(&selection_arg, word_list_arg.as_deref().unwrap_or_default())?;
+        }
+    }
+
+    // A puzzle ID already says how long its answer is.
+    if let Selection::Puzzle(id) = options.selection {
+        if length_arg.is_some() && options.word_length != id.get_word_length() {
+            check_conflict(&length_arg, "--puzzle")?;
+        }
+
+        options.word_length = id.get_word_length();
+    }
+
+    options.word_length_set = length_arg.is_some();
+
+    // Each mode has its own guess budget, e.g. Absurdle is hopeless within 6
+    // guesses so it is unlimited, unless another one is asked for.
+    if guesses_arg.is_none() {
+        options.max_guesses = options.mode.get_default_max_guesses();
+    }
+
+    Ok(options)
+}
+
+fn check_conflict(previous_arg: &Option<String>, arg: &str) -> Result<(), AppError> {
+    match previous_arg {
+        Some(previous_arg) => Err(AppError::ConflictingArgumentsErr(
+            previous_arg.clone(), arg.to_string()
+        )),
+        None => Ok(()),
+    }
+}
+
+fn next_value<I: Iterator<Item = String>>(arg: &str, args: &mut I) -> Result<String, AppError> {
+    args.next().ok_or_else(|| AppError::MissingArgumentValueErr(arg.to_string()))
+}
+
+fn parse_word_length(value: &str) -> Result<usize, AppError> {
+    match value.parse::<usize>() {
+        Ok(len) if (MIN_WORD_LENGTH..=MAX_WORD_LENGTH).contains(&len) => Ok(len),
+        _ => Err(AppError::InvalidWordLengthErr(value.to_string())),
+    }
+}
+
+fn parse_board_count(value: &str) -> Result<usize, AppError> {
+    match value.parse::<usize>() {
+        Ok(count) if BOARD_COUNTS.contains(&count) => Ok(count),
+        _ => Err(AppError::InvalidBoardCountErr(value.to_string())),
+    }
+}
+
+// Accepts either a number of guesses or "unlimited" for practice games.
+fn parse_max_guesses(value: &str) -> Result<Option<usize>, AppError> {
+    if value.eq_ignore_ascii_case("unlimited") {
+        return Ok(None);
+    }
+
+    match value.parse::<usize>() {
+        Ok(count) if (1..=GUESS_LIMIT).contains(&count) => Ok(Some(count)),
+        _ => Err(AppError::InvalidGuessCountErr(value.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Options, AppError> {
+        parse_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn test_parse_args_length() {
+        assert_eq!(WORD_LENGTH, parse(&[]).unwrap().get_word_length());
+        assert_eq!(6, parse(&["--length", "6"]).unwrap().get_word_length());
+        assert_eq!(4, parse(&["-l", "4"]).unwrap().get_word_length());
+        assert!(!parse(&[]).unwrap().is_word_length_set());
+        assert!(parse(&["-l", "5"]).unwrap().is_word_length_set());
+
+        for value in ["3", "9", "five", ""] {
+            assert_eq!(
+                Err(AppError::InvalidWordLengthErr(value.to_string())),
+                parse(&["--length", value])
+            );
+        }
+
+        assert_eq!(
+            Err(AppError::MissingArgumentValueErr("--length".to_string())),
+            parse(&["--length"])
+        );
+    }
+
+    #[test]
+    fn test_parse_args_guesses() {
+        assert_eq!(Some(MAX_GUESSES), parse(&[]).unwrap().get_max_guesses());
+        assert_eq!(Some(3), parse(&["--guesses", "3"]).unwrap().get_max_guesses());
+        assert_eq!(None, parse(&["-g", "unlimited"]).unwrap().get_max_guesses());
+        assert_eq!(None, parse(&["-g", "Unlimited"]).unwrap().get_max_guesses());
+
+        for value in ["0", "21", "-1", "many"] {
+            assert_eq!(
+                Err(AppError::InvalidGuessCountErr(value.to_string())),
+                parse(&["--guesses", value])
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_args_hard() {
+        assert!(!parse(&[]).unwrap().is_hard_mode());
+        assert!(parse(&["--hard", "-l", "6"]).unwrap().is_hard_mode());
+    }
+
+    #[test]
+    fn test_parse_args_daily() {
+        assert!(!parse(&[]).unwrap().is_daily());
+        assert!(parse(&["--daily"]).unwrap().is_daily());
+    }
+
+    #[test]
+    fn test_parse_args_seed_and_puzzle() {
+        assert_eq!(Selection::Random, parse(&[]).unwrap().get_selection());
+        assert_eq!(Selection::Seed(42), parse(&["--seed", "42"]).unwrap().get_selection());
+        assert_eq!(
+            Err(AppError::InvalidSeedErr("-1".to_string())),
+            parse(&["--seed", "-1"])
+        );
+
+        let options = parse(&["--puzzle", "6-10"]).unwrap();
+        assert_eq!(Selection::Puzzle(PuzzleId::parse("6-10").unwrap()), options.get_selection());
+        assert_eq!(6, options.get_word_length());
+        assert_eq!(6, parse(&["-l", "6", "--puzzle", "6-10"]).unwrap().get_word_length());
+
+        assert_eq!(
+            Err(AppError::ConflictingArgumentsErr("-l".to_string(), "--puzzle".to_string())),
+            parse(&["-l", "5", "--puzzle", "6-10"])
+        );
+        assert_eq!(
+            Err(AppError::ConflictingArgumentsErr("--daily".to_string(), "--seed".to_string())),
+            parse(&["--daily", "--seed", "1"])
+        );
+    }
+
+    #[test]
+    fn test_parse_args_absurdle() {
+        assert_eq!(Mode::Classic, parse(&[]).unwrap().get_mode());
+
+        let options = parse(&["--absurdle"]).unwrap();
+        assert_eq!(Mode::Absurdle, options.get_mode());
+        assert_eq!(None, options.get_max_guesses());
+        assert_eq!(Some(8), parse(&["-g", "8", "--absurdle"]).unwrap().get_max_guesses());
+
+        assert_eq!(
+            Err(AppError::ConflictingArgumentsErr("--daily".to_string(), "--absurdle".to_string())),
+            parse(&["--daily", "--absurdle"])
+        );
+    }
+
+    #[test]
+    fn test_parse_args_boards() {
+        let options = parse(&["--boards", "4"]).unwrap();
+        assert_eq!(Mode::MultiBoard(4), options.get_mode());
+        assert_eq!(Some(9), options.get_max_guesses());
+
+        assert_eq!(Some(7), parse(&["-b", "2"]).unwrap().get_max_guesses());
+        assert_eq!(Some(13), parse(&["-b", "8", "--seed", "3"]).unwrap().get_max_guesses());
+        assert_eq!(Some(20), parse(&["-g", "20", "-b", "8"]).unwrap().get_max_guesses());
+
+        for value in ["1", "3", "16", "two"] {
+            assert_eq!(
+                Err(AppError::InvalidBoardCountErr(value.to_string())),
+                parse(&["--boards", value])
+            );
+        }
+
+        assert_eq!(
+            Err(AppError::ConflictingArgumentsErr("--absurdle".to_string(), "-b".to_string())),
+            parse(&["--absurdle", "-b", "2"])
+        );
+        assert_eq!(
+            Err(AppError::ConflictingArgumentsErr("--daily".to_string(), "--boards".to_string())),
+            parse(&["--boards", "2", "--daily"])
+        );
+    }
+
+    #[test]
+    fn test_parse_args_reverse() {
+        let options = parse(&["--reverse", "-l", "6"]).unwrap();
+        assert_eq!(Mode::Reverse, options.get_mode());
+        assert_eq!(Some(MAX_GUESSES), options.get_max_guesses());
+
+        assert_eq!(
+            Err(AppError::ConflictingArgumentsErr("--seed".to_string(), "--reverse".to_string())),
+            parse(&["--seed", "1", "--reverse"])
+        );
+        assert_eq!(
+            Err(AppError::ConflictingArgumentsErr("--reverse".to_string(), "--boards".to_string())),
+            parse(&["--reverse", "--boards", "2"])
+        );
+        assert_eq!(
+            Err(AppError::ConflictingArgumentsErr("--hard".to_string(), "--mode".to_string())),
+            parse(&["--hard", "--mode", "reverse"])
+        );
+        assert_eq!(
+            Err(AppError::ConflictingArgumentsErr("--hard".to_string(), "--reverse".to_string())),
+            parse(&["--reverse", "--hard"])
+        );
+    }
+
+    #[test]
+    fn test_parse_args_mode() {
+        assert_eq!(Mode::Absurdle, parse(&["--mode", "absurdle"]).unwrap().get_mode());
+        assert_eq!(Mode::Reverse, parse(&["-m", "Reverse"]).unwrap().get_mode());
+
+        let options = parse(&["--mode", "octordle"]).unwrap();
+        assert_eq!(Mode::MultiBoard(8), options.get_mode());
+        assert_eq!(Some(13), options.get_max_guesses());
+
+        assert_eq!(
+            Err(AppError::InvalidModeErr("wordle".to_string())),
+            parse(&["--mode", "wordle"])
+        );
+        assert_eq!(
+            Err(AppError::ConflictingArgumentsErr("--mode".to_string(), "--absurdle".to_string())),
+            parse(&["--mode", "classic", "--absurdle"])
+        );
+        assert_eq!(
+            Err(AppError::ConflictingArgumentsErr("--puzzle".to_string(), "-m".to_string())),
+            parse(&["--puzzle", "5-70", "-m", "reverse"])
+        );
+    }
+
+    #[test]
+    fn test_parse_args_word_list() {
+        assert_eq!(&WordList::Common, parse(&[]).unwrap().get_word_list());
+        assert_eq!(&WordList::Full, parse(&["--word-list", "full"]).unwrap().get_word_list());
+        assert_eq!(&WordList::Full, parse(&["-w", "full", "--seed", "2"]).unwrap().get_word_list());
+
+        assert_eq!(
+            &WordList::Pack("tech".to_string()),
+            parse(&["--word-list", "Tech"]).unwrap().get_word_list()
+        );
+        assert_eq!(
+            Err(AppError::InvalidWordListErr("my pack".to_string())),
+            parse(&["--word-list", "my pack"])
+        );
+        assert_eq!(
+            Err(AppError::ConflictingArgumentsErr("--daily".to_string(), "-w".to_string())),
+            parse(&["-w", "full", "--daily"])
+        );
+    }
+
+    #[test]
+    fn test_parse_args_data_dir() {
+        assert_eq!(None, parse(&[]).unwrap().get_data_dir());
+        assert_eq!(
+            Some(&PathBuf::from("/tmp/wordle")),
+            parse(&["--data-dir", "/tmp/wordle"]).unwrap().get_data_dir()
+        );
+        assert_eq!(
+            Err(AppError::MissingArgumentValueErr("--data-dir".to_string())),
+            parse(&["--data-dir"])
+        );
+    }
+
+    #[test]
+    fn test_parse_args_word_list_files() {
+        let options = parse(&["--answers", "a.txt", "--allowed", "b.txt"]).unwrap();
+
+        assert_eq!(
+            &WordList::Files { answers: Some(PathBuf::from("a.txt")), allowed: Some(PathBuf::from("b.txt")) },
+            options.get_word_list()
+        );
+        assert_eq!(
+            &WordList::Files { answers: None, allowed: Some(PathBuf::from("b.txt")) },
+            parse(&["--allowed", "b.txt"]).unwrap().get_word_list()
+        );
+
+        assert_eq!(
+            Err(AppError::ConflictingArgumentsErr("-w".to_string(), "--answers".to_string())),
+            parse(&["-w", "full", "--answers", "a.txt"])
+        );
+        assert_eq!(
+            Err(AppError::ConflictingArgumentsErr("--puzzle".to_string(), "--allowed".to_string())),
+            parse(&["--puzzle", "5-70", "--allowed", "b.txt"])
+        );
+        assert_eq!(
+            Err(AppError::MissingArgumentValueErr("--answers".to_string())),
+            parse(&["--answers"])
+        );
+    }
+
+    #[test]
+    fn test_parse_args_commands() {
+        assert_eq!(Command::Play, parse(&[]).unwrap().get_command());
+        assert_eq!(Command::ShowHelp, parse(&["-l", "6", "--help"]).unwrap().get_command());
+        assert_eq!(Command::ShowVersion, parse(&["-V"]).unwrap().get_command());
+        assert_eq!(Command::ShowStats, parse(&["--stats"]).unwrap().get_command());
+
+        // Nothing after the help is looked at.
+        assert_eq!(Command::ShowHelp, parse(&["-h", "--colour"]).unwrap().get_command());
+    }
+
+    #[test]
+    fn test_parse_args_no_color() {
+        assert!(parse(&[]).unwrap().is_color_enabled());
+        assert!(!parse(&["--no-color"]).unwrap().is_color_enabled());
+    }
+
+    #[test]
+    fn test_usage_lists_every_option() {
+        let usage = get_usage();
+
+        for arg in ["--mode", "--length", "--guesses", "--hard", "--seed", "--daily", 
+                    "--word-list", "--no-color", "--data-dir", "--stats", "--help", "--version"] {
+            assert!(usage.contains(arg), "{} is missing from the usage", arg);
+        }
+    }
+
+    #[test]
+    fn test_parse_args_unknown() {
+        assert_eq!(
+            Err(AppError::UnknownArgumentErr("--colour".to_string())),
+            parse(&["--colour"])
+        );
+    }
+}
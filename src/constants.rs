@@ -1,2 +1,6 @@
-pub const MAX_GUESSES: usize = 5;
-pub const WORD_LENGTH: usize = 5;
\ No newline at end of file
+pub const MAX_GUESSES: usize = 6;
+pub const GUESS_LIMIT: usize = 20;
+pub const WORD_LENGTH: usize = 5;
+pub const MIN_WORD_LENGTH: usize = 4;
+pub const MAX_WORD_LENGTH: usize = 8;
+pub const BOARD_COUNTS: [usize; 3] = [2, 4, 8];
\ No newline at end of file
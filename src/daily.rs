@@ -0,0 +1,241 @@
+// The daily puzzle: one answer per calendar day, the same for every player, and
+// a record of who already finished it.
+
+extern crate chrono;
+
+use std::fs;
+use std::path::PathBuf;
+
+use chrono::{Local, NaiveDate};
+
+use crate::wordbank::WordBank;
+use crate::store;
+use crate::utils;
+
+// Daily puzzle #0 is the puzzle of this date.
+const FIRST_PUZZLE_DATE: (i32, u32, u32) = (2022, 1, 1);
+
+pub fn get_today() -> NaiveDate {
+    Local::now().date_naive()
+}
+
+// There is no puzzle before the first one, e.g. when the clock is wrong.
+pub fn get_puzzle_number(date: NaiveDate) -> Option<u32> {
+    let (year, month, day) = FIRST_PUZZLE_DATE;
+    let first_puzzle_date = NaiveDate::from_ymd_opt(year, month, day).unwrap();
+
+    if date < first_puzzle_date {
+        return None;
+    }
+
+    Some((date - first_puzzle_date).num_days() as u32)
+}
+
+pub fn get_daily_word(wordbank: &WordBank, puzzle_number: u32) -> String {
+    let unknown_words = wordbank.get_unknown_words();
+
+    assert_ne!(0, unknown_words.len());
+
+    let index = splitmix64(puzzle_number as u64) % (unknown_words.len() as u64);
+
+    unknown_words[index as usize].clone()
+}
+
+// A fixed mixing function, so that the daily answers do not change when the
+// rand crate changes its generators.
+fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct DailyResult {
+    puzzle_number: u32,
+    word_length: usize,
+    username: String,
+    guesses: Option<usize>,
+}
+
+impl DailyResult {
+    // `guesses` is the number of guesses it took to win, or `None` on a loss.
+    pub fn new(
+        puzzle_number: u32,
+        word_length: usize,
+        username: String,
+        guesses: Option<usize>,
+    ) -> DailyResult {
+        DailyResult { puzzle_number, word_length, username, guesses }
+    }
+
+    pub fn get_puzzle_number(&self) -> u32 {
+        self.puzzle_number
+    }
+
+    pub fn get_username(&self) -> &String {
+        &self.username
+    }
+
+    pub fn get_guesses(&self) -> Option<usize> {
+        self.guesses
+    }
+
+    // Whether this is the result of the player for that puzzle.
+    fn is_for(&self, puzzle_number: u32, word_length: usize, username: &str) -> bool {
+        self.puzzle_number == puzzle_number && self.word_length == word_length && self.username == username
+    }
+
+    fn to_line(&self) -> String {
+        let guesses = match self.guesses {
+            Some(guesses) => guesses.to_string(),
+            None => "X".to_string(),
+        };
+
+        format!("{} {} {} {}", self.puzzle_number, self.word_length, guesses, self.username)
+    }
+
+    fn from_line(line: &str) -> Option<DailyResult> {
+        let fields: Vec<&str> = line.splitn(4, ' ').collect();
+
+        if fields.len() != 4 || fields[3].is_empty() {
+            return None;
+        }
+
+        let guesses = match fields[2] {
+            "X" => None,
+            guesses => Some(guesses.parse().ok()?),
+        };
+
+        Some(DailyResult {
+            puzzle_number: fields[0].parse().ok()?,
+            word_length: fields[1].parse().ok()?,
+            username: fields[3].to_string(),
+            guesses,
+        })
+    }
+}
+
+// Daily results are kept apart from the free-play high scores.
+#[derive(Debug)]
+pub struct DailyResults {
+    results: Vec<DailyResult>,
+    daily_text_path: PathBuf,
+}
+
+impl DailyResults {
+    pub fn init() -> DailyResults {
+        let mut daily_text_path = utils::get_data_dir();
+
+        daily_text_path.push("daily.txt");
+
+        let results = match fs::read_to_string(&daily_text_path) {
+            Ok(daily_str) => parse_results(&daily_str),
+            Err(_) => Vec::new(),
+        };
+
+        DailyResults { results, daily_text_path }
+    }
+
+    pub fn get_result(
+        &self,
+        puzzle_number: u32,
+        word_length: usize,
+        username: &str,
+    ) -> Option<&DailyResult> {
+        self.results.iter().find(|result| result.is_for(puzzle_number, word_length, username))
+    }
+
+    // Only the first result of a player for a puzzle is kept, e.g. when the
+    // same puzzle was finished in two games at once.
+    pub fn insert_result(&mut self, result: DailyResult) {
+        self.results = store::update_entries(
+            &self.daily_text_path,
+            &self.results,
+            parse_results,
+            |results| insert_unique(results, result.clone()),
+            to_text,
+        );
+    }
+}
+
+fn insert_unique(results: &mut Vec<DailyResult>, result: DailyResult) {
+    if !results.iter().any(|r| r.is_for(result.puzzle_number, result.word_length, &result.username)) {
+        results.push(result);
+    }
+}
+
+// Lines that cannot be parsed are dropped rather than stopping the game.
+fn parse_results(daily_str: &str) -> Vec<DailyResult> {
+    daily_str.lines().filter_map(DailyResult::from_line).collect()
+}
+
+fn to_text(results: &[DailyResult]) -> String {
+    let mut buf = String::new();
+
+    for result in results {
+        buf.push_str(&result.to_line());
+        buf.push('\n');
+    }
+
+    buf
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_puzzle_number() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+
+        assert_eq!(Some(0), get_puzzle_number(date(2022, 1, 1)));
+        assert_eq!(Some(1), get_puzzle_number(date(2022, 1, 2)));
+        assert_eq!(Some(365), get_puzzle_number(date(2023, 1, 1)));
+        assert_eq!(None, get_puzzle_number(date(2021, 12, 31)));
+    }
+
+    #[test]
+    fn test_get_daily_word() {
+        let wordbank = WordBank::init();
+
+        for puzzle_number in [0, 1, 300, 1000] {
+            let word = get_daily_word(&wordbank, puzzle_number);
+
+            assert!(wordbank.in_unknown_words(&word));
+            assert_eq!(word, get_daily_word(&wordbank, puzzle_number));
+        }
+
+        assert_ne!(get_daily_word(&wordbank, 1), get_daily_word(&wordbank, 2));
+    }
+
+    #[test]
+    fn test_daily_result_line() {
+        let results = [
+            DailyResult::new(12, 5, "abcde".to_string(), Some(4)),
+            DailyResult::new(300, 7, "a b c".to_string(), None),
+        ];
+
+        for result in results {
+            assert_eq!(Some(result.clone()), DailyResult::from_line(&result.to_line()));
+        }
+
+        for line in ["", "12 5 4", "12 5 four abcde", "x 5 4 abcde"] {
+            assert_eq!(None, DailyResult::from_line(line));
+        }
+    }
+
+    #[test]
+    fn test_insert_unique() {
+        let mut results = vec![DailyResult::new(12, 5, "abcde".to_string(), Some(4))];
+
+        insert_unique(&mut results, DailyResult::new(12, 5, "abcde".to_string(), None));
+        insert_unique(&mut results, DailyResult::new(12, 6, "abcde".to_string(), Some(3)));
+        insert_unique(&mut results, DailyResult::new(13, 5, "abcde".to_string(), Some(2)));
+        insert_unique(&mut results, DailyResult::new(12, 5, "vwxyz".to_string(), Some(5)));
+
+        assert_eq!(4, results.len());
+        assert_eq!(Some(4), results[0].get_guesses());
+    }
+}
@@ -3,15 +3,25 @@ extern crate colored;
 use colored::*;
 
 use std::io;
+use std::path::Path;
+use std::process;
 
-use crate::constants::{WORD_LENGTH, MAX_GUESSES};
+use crate::game::Rules;
 use crate::user_input::IfWinner;
 use crate::err::AppError;
 use crate::highscore::UserScore;
+use crate::puzzle::PuzzleId;
+use crate::save::SavedGame;
+use crate::score_model::{self, ScoreBreakdown};
+use crate::scoring::{Feedback, LetterScore};
+use crate::share::{self, ShareStyle};
+use crate::stats::PlayerStats;
+use crate::solver::{GuessAnalysis, Hint};
 use crate::utils;
+use crate::wordpack::WordPack;
 
 pub type LetterColorMapping = (char, FontColors);
-pub type GuessColorMapping = [LetterColorMapping; WORD_LENGTH];
+pub type GuessColorMapping = Vec<LetterColorMapping>;
 
 #[derive(Copy, Clone, PartialEq, Debug)]
 pub enum FontColors {
@@ -20,70 +30,140 @@ pub enum FontColors {
     Gray,
 }
 
+impl FontColors {
+    // Green beats blue, which beats gray, when a letter got several colors.
+    fn rank(self) -> u8 {
+        match self {
+            FontColors::Gray  => 0,
+            FontColors::Blue  => 1,
+            FontColors::Green => 2,
+        }
+    }
+}
+
+impl From<LetterScore> for FontColors {
+    fn from(score: LetterScore) -> FontColors {
+        match score {
+            LetterScore::Correct => FontColors::Green,
+            LetterScore::Present => FontColors::Blue,
+            LetterScore::Absent  => FontColors::Gray,
+        }
+    }
+}
+
+// The keys of the on-screen keyboard, row by row.
+const KEYBOARD_ROWS: [&str; 3] = ["QWERTYUIOP", "ASDFGHJKL", "ZXCVBNM"];
+
+// The best color each letter got so far, from A to Z. Letters that were never
+// guessed have none.
+pub type KeyColors = [Option<FontColors>; 26];
+
+pub fn get_key_color(key_colors: &KeyColors, letter: char) -> Option<FontColors> {
+    key_colors[(letter.to_ascii_uppercase() as u8 - b'A') as usize]
+}
+
+fn update_key_colors(key_colors: &mut KeyColors, guess: &GuessColorMapping) {
+    for &(letter, color) in guess {
+        let key = &mut key_colors[(letter as u8 - b'A') as usize];
+
+        if key.is_none_or(|known| known.rank() < color.rank()) {
+            *key = Some(color);
+        }
+    }
+}
+
 pub struct Display {
-    allowed_letters: Vec<char>,
-    invalid_letters: Vec<char>,
+    key_colors: KeyColors,
     user_guess: GuessColorMapping,
-    user_guess_arr: [GuessColorMapping; MAX_GUESSES],
+    user_guess_arr: Vec<GuessColorMapping>,
+    max_guesses: Option<usize>,
+    title: Option<String>,
 }
 
 impl Display {
-    pub fn init() -> Display {
-        let allowed_letters: Vec<char> = ('A'..='Z').collect();
-        let invalid_letters: Vec<char> = Vec::new();
-        let user_guess = [(' ', FontColors::Gray); WORD_LENGTH];
-        let user_guess_arr = [user_guess; MAX_GUESSES];
+    pub fn init(rules: Rules) -> Display {
+        let user_guess = vec![(' ', FontColors::Gray); rules.get_word_length()];
+        let max_guesses = rules.get_max_guesses();
+        let user_guess_arr = vec![user_guess.clone(); max_guesses.unwrap_or(1)];
 
-        Display { allowed_letters, user_guess, invalid_letters, user_guess_arr }
+        Display {
+            key_colors: [None; 26], user_guess, user_guess_arr, max_guesses, title: None
+        }
     }
     
-    pub fn get_allowed_letters(&self) -> &Vec<char> {
-        &self.allowed_letters
+    pub fn get_key_colors(&self) -> &KeyColors {
+        &self.key_colors
     }
 
-    pub fn get_invalid_letters(&self) -> &Vec<char> {
-        &self.invalid_letters
+    pub fn set_key_colors(&mut self, key_colors: KeyColors) {
+        self.key_colors = key_colors;
     }
 
     pub fn get_user_guess(&mut self, user_guess: GuessColorMapping) {
         self.user_guess = user_guess;
     }
 
-    pub fn get_user_guess_arr(&self) -> [GuessColorMapping; MAX_GUESSES] {
-        self.user_guess_arr
+    pub fn get_user_guess_arr(&self) -> &Vec<GuessColorMapping> {
+        &self.user_guess_arr
     }
 
-    pub fn update_allowed_letters(&mut self) {
-        self.allowed_letters.retain(|l| !self.invalid_letters.contains(l));
+    // The title is printed above the board, e.g. to tell which puzzle is played.
+    pub fn set_title(&mut self, title: String) {
+        self.title = Some(title);
     }
 
-    pub fn update_invalid_letters(&mut self, invalid: char) {
-        self.invalid_letters.push(invalid);
+    // Folds the feedback of a guess into the keyboard, where every letter keeps
+    // the best color it got so far. A repeated letter that is gray in one spot
+    // and green or blue in another is in the word, so it is not grayed out.
+    pub fn update_key_colors_from(&mut self, feedback: &Feedback) {
+        update_key_colors(&mut self.key_colors, &to_guess_color_mapping(feedback));
     }
-    
+
     pub fn update_user_guess_arr(&mut self, guess_count: usize) {
-        self.user_guess_arr[guess_count] = self.user_guess;
+        self.user_guess_arr[guess_count] = self.user_guess.clone();
+
+        // Without a guess budget, the board grows by one empty row for the next guess.
+        if self.max_guesses.is_none() && guess_count + 1 == self.user_guess_arr.len() {
+            let empty_row = vec![(' ', FontColors::Gray); self.user_guess.len()];
+            self.user_guess_arr.push(empty_row);
+        }
     }
 
-    pub fn print_allowed_letters(&self) {
-        print!("{}", "Available Letters:".underline().bold());
-        print!("  ");
+    pub fn print_title(&self) {
+        if let Some(title) = &self.title {
+            println!("{}\n", title.bold());
+        }
+    }
 
-        for letter in 'A'..='Z' {
-            if self.allowed_letters.contains(&letter) {
-                print!("{} ", letter)
-            } else {
-                print!("  ");
+    // Prints a QWERTY keyboard, with every letter in the best color it got.
+    // Letters that are not in the word are dimmed, and the others stay plain.
+    pub fn print_keyboard(&self) {
+        for (i, row) in KEYBOARD_ROWS.iter().enumerate() {
+            print!("\t\t\t{}", " ".repeat(i));
+
+            for letter in row.chars() {
+                let key = letter.to_string();
+                let key = match get_key_color(&self.key_colors, letter) {
+                    Some(FontColors::Green) => key.green().bold(),
+                    Some(FontColors::Blue)  => key.blue().bold(),
+                    Some(FontColors::Gray)  => key.bright_black(),
+                    None                    => key.bold(),
+                };
+
+                print!("{} ", key);
             }
+
+            println!();
         }
-        println!("\n");
+
+        println!();
     }
 
     pub fn print_all_guesses(&self) {
-        for guess in self.user_guess_arr {
+        for guess in &self.user_guess_arr {
             print!("\t\t\t");
 
-            for letter in guess {
+            for &letter in guess {
                 print!("{} ", Self::colorize(letter));
             }
             println!("\n");
@@ -101,6 +181,123 @@ impl Display {
     }
 }
 
+// The boards of a multi-board game, printed side by side, and a keyboard that
+// shows the color of every letter on each board.
+pub struct MultiDisplay {
+    boards: Vec<Vec<GuessColorMapping>>,
+    key_colors: Vec<KeyColors>,
+    word_length: usize,
+    max_guesses: Option<usize>,
+    title: Option<String>,
+}
+
+impl MultiDisplay {
+    pub fn init(rules: Rules, board_count: usize) -> MultiDisplay {
+        let word_length = rules.get_word_length();
+        let max_guesses = rules.get_max_guesses();
+        let empty_board = vec![vec![(' ', FontColors::Gray); word_length]; max_guesses.unwrap_or(1)];
+
+        MultiDisplay {
+            boards: vec![empty_board; board_count],
+            key_colors: vec![[None; 26]; board_count],
+            word_length,
+            max_guesses,
+            title: None,
+        }
+    }
+
+    pub fn get_boards(&self) -> &Vec<Vec<GuessColorMapping>> {
+        &self.boards
+    }
+
+    pub fn get_key_colors(&self) -> &Vec<KeyColors> {
+        &self.key_colors
+    }
+
+    pub fn set_title(&mut self, title: String) {
+        self.title = Some(title);
+    }
+
+    pub fn update_board(&mut self, board: usize, guess_count: usize, guess: GuessColorMapping) {
+        update_key_colors(&mut self.key_colors[board], &guess);
+        self.boards[board][guess_count] = guess;
+
+        // Without a guess budget, every board grows by one empty row for the next guess.
+        if self.max_guesses.is_none() && guess_count + 1 == self.boards[board].len() {
+            let empty_row = vec![(' ', FontColors::Gray); self.word_length];
+
+            for rows in &mut self.boards {
+                rows.push(empty_row.clone());
+            }
+        }
+    }
+
+    pub fn print_title(&self) {
+        if let Some(title) = &self.title {
+            println!("{}\n", title.bold());
+        }
+    }
+
+    // Every key is followed by one mark per board, colored like the letter is
+    // on that board.
+    pub fn print_keyboard(&self) {
+        for (i, row) in KEYBOARD_ROWS.iter().enumerate() {
+            print!("\t{}", " ".repeat(i * (self.key_colors.len() + 2) / 2));
+
+            for letter in row.chars() {
+                print!("{}", letter.to_string().bold());
+
+                for key_colors in &self.key_colors {
+                    let mark = match get_key_color(key_colors, letter) {
+                        Some(FontColors::Green) => "■".green(),
+                        Some(FontColors::Blue)  => "■".blue(),
+                        Some(FontColors::Gray)  => "·".normal(),
+                        None                    => " ".normal(),
+                    };
+
+                    print!("{}", mark);
+                }
+
+                print!(" ");
+            }
+
+            println!();
+        }
+
+        println!();
+    }
+
+    // Prints at most four boards per line so that Octordle still fits.
+    pub fn print_all_guesses(&self) {
+        const BOARDS_PER_LINE: usize = 4;
+
+        for boards in self.boards.chunks(BOARDS_PER_LINE) {
+            for row in 0..boards[0].len() {
+                print!("\t");
+
+                for board in boards {
+                    for &letter in &board[row] {
+                        print!("{} ", Display::colorize(letter));
+                    }
+
+                    print!("   ");
+                }
+
+                println!("\n");
+            }
+
+            println!();
+        }
+    }
+}
+
+pub fn to_guess_color_mapping(feedback: &Feedback) -> GuessColorMapping {
+    feedback.get_letters()
+        .iter()
+        .map(|&(letter, score)| (letter.to_ascii_uppercase(), score.into()))
+        .collect()
+}
+
 pub fn display_high_score(high_scores: &[UserScore]) {
     println!("\t\t{:=^30}\n", "HIGHSCORES");
     
@@ -119,33 +316,176 @@ pub fn display_high_score(high_scores: &[UserScore]) {
     println!("\t\t{:=^30}\n", "");
 }
 
-pub fn display_start_screen(high_scores: &[UserScore]) -> char {
+pub fn display_stats_summary(players: &[PlayerStats]) {
+    println!("\t\t{:=^30}\n", "STATISTICS");
+
+    if players.is_empty() {
+        println!("\t\t{: ^30}\n", "No games played yet!");
+    } else {
+        println!("\t\t{:<10}{:>5}{:>5}{:>5}{:>5}", "", "Play", "Win%", "Cur", "Max");
+
+        for player in players {
+            println!(
+                "\t\t{:<10}{:>5}{:>5}{:>5}{:>5}", 
+                player.get_username(), player.get_played(), player.get_win_percentage(),
+                player.get_current_streak(), player.get_max_streak()
+            );
+        }
+        println!(" ");
+    }
+
+    println!("\t\t{:=^30}\n", "");
+}
+
+// Prints the statistics of a player the way the original game does, with the
+// bar of the last game's guess count highlighted.
+pub fn display_player_stats(stats: &PlayerStats, last_guesses: Option<usize>) {
+    const MAX_BAR_WIDTH: u32 = 30;
+    const MIN_ROWS: usize = 6;
+
+    println!("\n\t\t{:=^40}\n", format!(" STATISTICS: {} ", stats.get_username()));
+    println!(
+        "\t\t{:^10}{:^10}{:^10}{:^10}", 
+        stats.get_played(), stats.get_win_percentage(), 
+        stats.get_current_streak(), stats.get_max_streak()
+    );
+    println!("\t\t{:^10}{:^10}{:^10}{:^10}\n", "Played", "Win %", "Current", "Max");
+    println!("\t\t{:^40}\n", "GUESS DISTRIBUTION");
+
+    let distribution = stats.get_guess_distribution();
+    let most_wins = distribution.iter().copied().max().unwrap_or(0).max(1);
+    let rows = distribution.len().max(MIN_ROWS);
+
+    for i in 0..rows {
+        let count = distribution.get(i).copied().unwrap_or(0);
+        let width = ((count * MAX_BAR_WIDTH) as f64 / most_wins as f64).ceil() as usize;
+        let bar = format!("{} {}", "█".repeat(width), count);
+
+        if last_guesses == Some(i + 1) {
+            println!("\t\t{:>3} | {}", i + 1, bar.green().bold());
+        } else {
+            println!("\t\t{:>3} | {}", i + 1, bar);
+        }
+    }
+
+    println!("\n\t\t{:=^40}", "");
+}
+
+// The word packs are only offered when the words can still be changed, and a
+// saved round only when there is one. Problems that did not stop the game are
+// shown first.
+pub fn display_start_screen(
+        high_scores: &[UserScore], 
+        players: &[PlayerStats], 
+        can_pick_pack: bool, 
+        saved_games: &[SavedGame],
+        warnings: &[&AppError]
+        ) -> char {
     println!("\nLet's play Wordle!\n");
+
+    for warning in warnings {
+        println!("{}\n", warning.to_str::<()>(None).yellow());
+    }
+
     display_high_score(high_scores);
+    display_stats_summary(players);
+
+    let mut commands: Vec<(char, String)> = Vec::new();
+
+    match saved_games {
+        [] => (),
+        [saved_game] => commands.push(('R', format!(
+            "resume the round of {} ({} guesses so far)", 
+            saved_game.get_username(), saved_game.get_guesses().len()
+        ))),
+        _ => commands.push(('R', format!("resume one of the {} saved rounds", saved_games.len()))),
+    }
+
+    commands.push(('P', "play the game".to_string()));
+    commands.push(('H', "display the mechanics of the game".to_string()));
+
+    if can_pick_pack {
+        commands.push(('W', "pick a word pack".to_string()));
+    }
+
+    // The first choice starts the sentence.
+    let choices: Vec<String> = commands.iter()
+        .enumerate()
+        .map(|(i, (cmd, action))| format!(
+            "{} {} to {}", if i == 0 { "Press" } else { "press" }, cmd.to_string().underline(), action
+        ))
+        .collect();
+
     println!(
-        "Press {} to play the game, press {} to display the mechanics of the game, or press {} to exit the game.\n", 
-        "P".underline(), "H".underline(), "Q".underline() 
+        "{}, or press {} to exit the game.\n", 
+        choices.join(", "), "Q".underline()
     );
-    
-    loop {
-        let mut command = String::new();
 
-        io::stdin()
-          .read_line(&mut command)
-          .expect("Error reading input.");
+    let mut valid_commands: Vec<char> = commands.iter().map(|&(cmd, _)| cmd).collect();
+    valid_commands.push('Q');
 
-        match is_command_valid(&command, vec!['P', 'H', 'Q']) {
-            Ok(cmd) => return cmd,
-            Err(err) => eprintln!("{}\n", err),
-        }
+    read_command(valid_commands)
+}
+
+// Lists the word packs and returns the index of the one picked, if any. Packs
+// that could not be loaded are reported after the list.
+pub fn display_word_packs(packs: &[WordPack], errors: &[AppError]) -> Option<usize> {
+    utils::clear_screen();
+
+    println!("{}\n", "Word packs".bold());
+
+    for (i, pack) in packs.iter().enumerate() {
+        println!(
+            "{:>3}. {} ({} letters, {} answers): {}", 
+            i + 1, pack.get_name().bold(), pack.get_word_length(), 
+            pack.get_answers().len(), pack.get_description()
+        );
+    }
+
+    for err in errors {
+        println!("\n{}", err.to_str::<()>(None).yellow());
+    }
+
+    println!("\nEnter the number of a pack, or nothing to keep the current words:");
+
+    read_choice(packs.len(), AppError::InvalidPackChoiceErr)
+}
+
+// Lists the saved rounds of every player and returns the index of the one
+// picked, if any.
+pub fn display_saved_games(saved_games: &[SavedGame]) -> Option<usize> {
+    utils::clear_screen();
+
+    println!("{}\n", "Saved rounds".bold());
+
+    for (i, saved_game) in saved_games.iter().enumerate() {
+        println!(
+            "{:>3}. {} ({} letters, {} guesses so far)", 
+            i + 1, saved_game.get_username().bold(), saved_game.get_rules().get_word_length(), 
+            saved_game.get_guesses().len()
+        );
     }
+
+    println!("\nEnter the number of a round, or nothing to start a new one:");
+
+    read_choice(saved_games.len(), AppError::InvalidSavedGameChoiceErr)
 }
 
-pub fn display_help() {
+pub fn display_help(rules: Rules) {
     utils::clear_screen();
 
+    let tries = match rules.get_max_guesses() {
+        Some(max_guesses) => format!("{} tries", max_guesses),
+        None => "as many tries as you need".to_string(),
+    };
+
+    let hard_mode = if rules.is_hard_mode() {
+        "\nHard mode is on: every GREEN letter must stay in its position and \n\
+        every BLUE letter must be reused in your next guesses.\n"
+    } else { "" };
+
     println!("
-    Game Mechanics: Guess the five-letter word in five tries. \
+    Game Mechanics: Guess the {}-letter word in {}. \
     Your guess will change color depending on its correctness.\n\n\
     For example, if the unknown word is 'altar', and your guess is \n\
     'later', then your guess will be displayed as {} {} {} {} {}, \n\
@@ -154,41 +494,135 @@ pub fn display_help() {
     swap to be in correct position), 'T' and 'R' are colored {} since \n\
     they can be found in the word 'altar' {} they are in the \n\
     correct position, while 'E' is colored {} since it cannot be found \n\
-    in the word 'altar'.\n\nUse these color hints to guess the unknown word!\n", 
-    "L".blue().bold(), "A".blue().bold(), "T".green().bold(), "E".bold(), 
+    in the word 'altar'.\n\nUse these color hints to guess the unknown word!\n\
+    Stuck? Enter '?' instead of a guess to get a hint, but the round will not count \n\
+    towards the high scores.\n{}\n{}\n", 
+    rules.get_word_length(), tries, "L".blue().bold(), "A".blue().bold(), "T".green().bold(), "E".bold(), 
     "R".green().bold(), "BLUE".blue().bold(), "but".italic(), 
-    "GREEN".green().bold(), "and".italic(), "GRAY".normal().bold()
+    "GREEN".green().bold(), "and".italic(), "GRAY".normal().bold(), hard_mode,
+    score_model::describe_formula()
     );
 
     
-    loop {
-        println!("Press P to play the game:");
-        
-        let mut command = String::new();
+    println!("Press P to play the game:");
+    read_command(vec!['P']);
+}
 
-        io::stdin()
-          .read_line(&mut command)
-          .expect("Error reading input.");
+// Lets the player print their result, or write it to a file, before moving on.
+// `share_text` builds the result in the given style.
+pub fn display_share_menu<F: Fn(ShareStyle) -> String>(share_text: F) {
+    const DEFAULT_SHARE_PATH: &str = "share.txt";
 
-        match is_command_valid(&command, vec!['P']) {
-            Ok(_) => return,
-            Err(err) => eprintln!("{}\n", err),
+    loop {
+        println!(
+            "\nPress {} to show your result to share, {} to show it without emojis, \
+            {} to save it to a file, or {} to go back.", 
+            "S".underline(), "A".underline(), "F".underline(), "C".underline()
+        );
+
+        match read_command(vec!['S', 'A', 'F', 'C']) {
+            'S' => println!("\n{}", share_text(ShareStyle::detect())),
+            'A' => println!("\n{}", share_text(ShareStyle::Ascii)),
+            'F' => {
+                println!("File to save the result to (default: {}):", DEFAULT_SHARE_PATH);
+
+                let mut path = String::new();
+
+                io::stdin()
+                  .read_line(&mut path)
+                  .expect("Error reading input.");
+
+                let path = match path.trim() {
+                    "" => DEFAULT_SHARE_PATH,
+                    path => path,
+                };
+
+                match share::write_share_text(Path::new(path), &share_text(ShareStyle::Emoji)) {
+                    Ok(()) => println!("Saved your result to {}.", path),
+                    Err(err) if err.kind() == io::ErrorKind::AlreadyExists => {
+                        eprintln!("{} already exists. Choose another file to keep it.", path)
+                    }
+                    Err(err) => eprintln!("IO Error: {}", err),
+                }
+            }
+            _ => return,
         }
     }
 }
 
-pub fn display_guesses_left(guesses_left: u8) {
-    if guesses_left == 0 {
+pub fn display_hint(hint: &Hint) {
+    let candidates_left = hint.get_candidates().len();
+
+    match candidates_left {
+        0 => println!("\nNo word in the list matches these clues."),
+        1 => println!("\n1 possible answer remains."),
+        _ => println!("\n{} possible answers remain.", candidates_left),
+    }
+
+    if !hint.get_suggestions().is_empty() {
+        println!("Suggested guesses:");
+    }
+
+    for suggestion in hint.get_suggestions() {
+        let word = suggestion.get_word().to_uppercase();
+        let word = if suggestion.is_candidate() { word.green().bold() } else { word.bold() };
+
+        println!(
+            "\t{}  {:.2} bits, at most {} left", 
+            word, suggestion.get_entropy(), suggestion.get_worst_case()
+        );
+    }
+
+    println!();
+}
+
+// Shows, guess by guess, how much closer each one got to the answer and what
+// the solver would have played at that point.
+pub fn display_analysis(analysis: &[GuessAnalysis]) {
+    println!("\n{}", "Your guesses:".bold());
+    println!("\t{:<10} {:>10} {:>10} {:>10} {:>10}   Best guess", "Guess", "Before", "After", "Bits", "Expected");
+
+    for guess in analysis {
+        let best_guess = match guess.get_best_guess() {
+            Some(best) => format!("{} ({:.2} bits)", best.get_word().to_uppercase(), best.get_entropy()),
+            None => "-".to_string(),
+        };
+
         println!(
+            "\t{:<10} {:>10} {:>10} {:>10.2} {:>10.2}   {}",
+            guess.get_guess().to_uppercase(), guess.get_candidates_before(),
+            guess.get_candidates_after(), guess.get_bits_gained(),
+            guess.get_expected_bits(), best_guess
+        );
+    }
+}
+
+pub fn display_guesses_left(guesses_left: Option<usize>) {
+    match guesses_left {
+        Some(0) => println!(
             "Number of guesses left: {}. {}", 
             "0".red().bold(), "GAME OVER".red().bold()
-        );
-    } else { println!("Number of guesses left: {}", guesses_left); }
+        ),
+        Some(guesses_left) => println!("Number of guesses left: {}", guesses_left),
+        None => println!("Number of guesses left: {}", "unlimited (practice)".italic()),
+    }
 }
 
 // Don't mind the maths, it just prints a fancy animation of the ending screen.
-pub fn display_end_screen(winner: IfWinner, unknown_answer: &str) {
+// The animation runs for a couple of seconds and then gives back control.
+pub fn display_end_screen(winner: IfWinner, unknown_answers: &[&str], puzzle_id: Option<PuzzleId>) {
     const WSPACE: u16 = 100;
+    const FRAMES: u16 = 40;
+
+    let puzzle_id = match puzzle_id {
+        Some(puzzle_id) => format!(" Puzzle ID: {}", puzzle_id),
+        None => String::new(),
+    };
+
+    let unknown_answers = match unknown_answers {
+        [unknown_answer] => format!("answer is {}", unknown_answer),
+        _ => format!("answers are {}", unknown_answers.join(", ")),
+    };
 
     utils::sleep_sec(3);
     utils::clear_screen();
@@ -198,7 +632,7 @@ pub fn display_end_screen(winner: IfWinner, unknown_answer: &str) {
         false => WSPACE,
     };
 
-    loop {
+    for _ in 0..FRAMES {
         if winner && n == WSPACE {
             n = 0;
         } else if !winner && n == 0 {
@@ -212,10 +646,10 @@ pub fn display_end_screen(winner: IfWinner, unknown_answer: &str) {
         }
 
         if winner {
-            print!("{}", "You won the game!\n".green().bold());
+            print!("{}", format!("You won the game!{}\n", puzzle_id).green().bold());
         } else {
             print!("{}", format!(
-                "You lost! The correct answer is {}.\n", unknown_answer).red().bold()
+                "You lost! The correct {}.{}\n", unknown_answers, puzzle_id).red().bold()
             );
         }
 
@@ -223,6 +657,104 @@ pub fn display_end_screen(winner: IfWinner, unknown_answer: &str) {
     }
 }
 
+pub fn display_score(breakdown: &ScoreBreakdown) {
+    let mut parts = vec![
+        format!("{} for guesses", breakdown.get_guess_points()),
+        format!("{} for time", breakdown.get_time_points()),
+        format!("{} for difficulty", breakdown.get_difficulty_points()),
+    ];
+
+    if breakdown.get_hard_mode_points() > 0 {
+        parts.push(format!("{} for hard mode", breakdown.get_hard_mode_points()));
+    }
+
+    println!(
+        "\nYour score: {} ({})", 
+        breakdown.get_total().to_string().bold(), parts.join(" + ")
+    );
+}
+
+// The analysis is only offered for games with a single board.
+pub fn display_end_menu(can_analyze: bool) -> char {
+    if can_analyze {
+        println!(
+            "\nPress {} to play again, press {} to show your statistics, press {} to analyze your guesses, \
+            press {} to share your result, or press {} to exit the game.\n", 
+            "P".underline(), "S".underline(), "A".underline(), "R".underline(), "Q".underline()
+        );
+
+        read_command(vec!['P', 'S', 'A', 'R', 'Q'])
+    } else {
+        println!(
+            "\nPress {} to play again, press {} to show your statistics, press {} to share your result, \
+            or press {} to exit the game.\n", 
+            "P".underline(), "S".underline(), "R".underline(), "Q".underline()
+        );
+
+        read_command(vec!['P', 'S', 'R', 'Q'])
+    }
+}
+
+pub fn display_play_again_menu() -> char {
+    println!(
+        "\nPress {} to play again, or press {} to exit the game.\n", 
+        "P".underline(), "Q".underline()
+    );
+
+    read_command(vec!['P', 'Q'])
+}
+
+// Reads commands until a valid one is entered. There is nothing left to do once
+// the input is closed, so the game exits then.
+fn read_command(valid_commands: Vec<char>) -> char {
+    loop {
+        let mut command = String::new();
+
+        let bytes_read = io::stdin()
+          .read_line(&mut command)
+          .expect("Error reading input.");
+
+        if bytes_read == 0 {
+            process::exit(0);
+        }
+
+        match is_command_valid(&command, valid_commands.clone()) {
+            Ok(cmd) => return cmd,
+            Err(err) => eprintln!("{}\n", err),
+        }
+    }
+}
+
+// Reads the number of one of `count` items until a valid one is entered, or
+// nothing for none of them.
+fn read_choice(count: usize, invalid: fn(usize) -> AppError) -> Option<usize> {
+    loop {
+        let mut input = String::new();
+
+        if io::stdin().read_line(&mut input).expect("Error reading input.") == 0 {
+            process::exit(0);
+        }
+
+        match parse_choice(&input, count, invalid) {
+            Ok(choice) => return choice,
+            Err(err) => eprintln!("{}", err.to_str::<()>(None)),
+        }
+    }
+}
+
+fn parse_choice(input: &str, count: usize, invalid: fn(usize) -> AppError) -> Result<Option<usize>, AppError> {
+    let input = input.trim();
+
+    if input.is_empty() {
+        return Ok(None);
+    }
+
+    match input.parse::<usize>() {
+        Ok(number) if (1..=count).contains(&number) => Ok(Some(number - 1)),
+        _ => Err(invalid(count)),
+    }
+}
+
 fn is_command_valid(command: &str, valid_commands: Vec<char>) -> Result<char, String> {
     let command: char = match command.trim().parse() {
         Ok(cmd) => cmd,
@@ -241,6 +773,20 @@ fn is_command_valid(command: &str, valid_commands: Vec<char>) -> Result<char, St
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::scoring;
+
+    #[test]
+    fn test_parse_choice() {
+        let invalid = AppError::InvalidPackChoiceErr;
+
+        assert_eq!(Ok(None), parse_choice("\n", 3, invalid));
+        assert_eq!(Ok(Some(0)), parse_choice(" 1\n", 3, invalid));
+        assert_eq!(Ok(Some(2)), parse_choice("3", 3, invalid));
+
+        for input in ["0", "4", "-1", "tech"] {
+            assert_eq!(Err(AppError::InvalidPackChoiceErr(3)), parse_choice(input, 3, invalid));
+        }
+    }
 
     #[test]
     fn test_is_command_valid() {
@@ -264,4 +810,116 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_update_user_guess_arr() {
+        let mut display = Display::init(Rules::new(5, Some(3), false));
+
+        display.update_user_guess_arr(0);
+        assert_eq!(3, display.get_user_guess_arr().len());
+
+        let mut display = Display::init(Rules::new(5, None, false));
+        assert_eq!(1, display.get_user_guess_arr().len());
+
+        display.update_user_guess_arr(0);
+        display.update_user_guess_arr(1);
+        assert_eq!(3, display.get_user_guess_arr().len());
+    }
+
+    #[test]
+    fn test_update_key_colors_from() {
+        let mut display = Display::init(Rules::default());
+
+        display.update_key_colors_from(&scoring::score("shell", "hello"));
+
+        let key_colors = *display.get_key_colors();
+        assert_eq!(Some(FontColors::Gray), get_key_color(&key_colors, 'S'));
+        assert_eq!(Some(FontColors::Blue), get_key_color(&key_colors, 'H'));
+        assert_eq!(Some(FontColors::Green), get_key_color(&key_colors, 'L'));
+        assert_eq!(None, get_key_color(&key_colors, 'O'));
+
+        // A later gray does not take back what was found before.
+        display.update_key_colors_from(&scoring::score("hotel", "hello"));
+
+        let key_colors = *display.get_key_colors();
+        assert_eq!(Some(FontColors::Green), get_key_color(&key_colors, 'H'));
+        assert_eq!(Some(FontColors::Green), get_key_color(&key_colors, 'L'));
+        assert_eq!(Some(FontColors::Gray), get_key_color(&key_colors, 'T'));
+    }
+
+    #[test]
+    fn test_update_key_colors_from_repeated_letters() {
+        // (guess, answer, letter, color of the letter on the keyboard)
+        let cases: [(&str, &str, char, FontColors); 6] = [
+            // Both Ls of "llama" are misplaced, not missing.
+            ("llama", "hello", 'L', FontColors::Blue),
+            ("llama", "hello", 'A', FontColors::Gray),
+            // The second T of "tenet" is gray, but the first one is blue.
+            ("tenet", "catch", 'T', FontColors::Blue),
+            // The second E of "speed" is gray, but the first one is blue.
+            ("speed", "abide", 'E', FontColors::Blue),
+            // The second E of "eerie" is gray, but the others are blue and green.
+            ("eerie", "there", 'E', FontColors::Green),
+            // Only the last E of "geese" is in the word.
+            ("geese", "those", 'E', FontColors::Green),
+        ];
+
+        for (guess, answer, letter, color) in cases {
+            let mut display = Display::init(Rules::default());
+
+            display.update_key_colors_from(&scoring::score(guess, answer));
+
+            assert_eq!(
+                Some(color), get_key_color(display.get_key_colors(), letter),
+                "{} for {} against {}", letter, guess, answer
+            );
+        }
+    }
+
+    #[test]
+    fn test_update_key_colors_from_several_guesses() {
+        let mut display = Display::init(Rules::default());
+
+        // The Ls are green first, and then only the extra L is gray.
+        display.update_key_colors_from(&scoring::score("hello", "world"));
+        display.update_key_colors_from(&scoring::score("lolly", "world"));
+
+        assert_eq!(Some(FontColors::Green), get_key_color(display.get_key_colors(), 'L'));
+        assert_eq!(Some(FontColors::Green), get_key_color(display.get_key_colors(), 'O'));
+        assert_eq!(Some(FontColors::Gray), get_key_color(display.get_key_colors(), 'Y'));
+    }
+
+    #[test]
+    fn test_multi_display_update_board() {
+        let mut display = MultiDisplay::init(Rules::new(5, None, false), 2);
+
+        display.update_board(0, 0, to_guess_color_mapping(&scoring::score("shell", "hello")));
+        display.update_board(1, 0, to_guess_color_mapping(&scoring::score("shell", "catch")));
+
+        // Each board keeps its own colors, and the best one for repeated letters.
+        let key = |board: usize, letter: char| display.get_key_colors()[board][(letter as u8 - b'A') as usize];
+
+        assert_eq!(Some(FontColors::Green), key(0, 'L'));
+        assert_eq!(Some(FontColors::Gray), key(1, 'L'));
+        assert_eq!(Some(FontColors::Blue), key(1, 'H'));
+        assert_eq!(None, key(0, 'Z'));
+
+        assert!(display.get_boards().iter().all(|board| board.len() == 2));
+    }
+
+    #[test]
+    fn test_to_guess_color_mapping() {
+        let gcm = to_guess_color_mapping(&scoring::score("tenet", "catch"));
+
+        assert_eq!(
+            vec![
+                ('T', FontColors::Blue),
+                ('E', FontColors::Gray),
+                ('N', FontColors::Gray),
+                ('E', FontColors::Gray),
+                ('T', FontColors::Gray),
+            ],
+            gcm
+        );
+    }
 }
\ No newline at end of file
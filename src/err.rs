@@ -2,24 +2,156 @@
 
 use std::fmt;
 
-#[derive(PartialEq)]
+#[derive(Clone, PartialEq, Debug)]
 pub enum AppError {
     InvalidCommandErr,
-    InvalidGuessLengthErr,
+    InvalidGuessLengthErr(usize),
     NonAlphaGuessErr,
     NotEnglishGuessErr,
+    GameOverErr,
+    HardModeMisplacedLetterErr(usize, char),
+    HardModeMissingLetterErr(char, usize),
+    UnknownArgumentErr(String),
+    MissingArgumentValueErr(String),
+    InvalidWordLengthErr(String),
+    InvalidGuessCountErr(String),
+    DailyAlreadyPlayedErr(u32),
+    NoDailyPuzzleErr,
+    InvalidSeedErr(String),
+    InvalidPuzzleIdErr(String),
+    ConflictingArgumentsErr(String, String),
+    InvalidBoardCountErr(String),
+    InvalidFeedbackErr(usize),
+    NoMatchingWordErr,
+    InvalidModeErr(String),
+    InvalidWordListErr(String),
+    WordListReadErr(String, String),
+    InvalidWordListFileErr(String, Vec<String>),
+    AnswersNotAllowedErr(Vec<String>),
+    NotEnoughAnswersErr(usize),
+    UnknownWordPackErr(String, Vec<String>),
+    WordPackLengthErr(String, usize),
+    InvalidPackChoiceErr(usize),
+    InvalidSavedGameErr,
+    InvalidSavedGameChoiceErr(usize),
+    CorruptHighScoresErr(Option<String>, Vec<String>),
+    NewerHighScoresErr(u32),
 }
 
 use AppError::*;
-use crate::constants::WORD_LENGTH;
+use crate::constants::{MIN_WORD_LENGTH, MAX_WORD_LENGTH, GUESS_LIMIT, BOARD_COUNTS};
+use crate::cli::Mode;
+use crate::utils;
+use crate::wordbank::WordList;
 
 impl AppError {
     pub fn to_str<T: fmt::Debug>(&self, arg: Option<&T>) -> String {
         match self {
             InvalidCommandErr => format!("\nInvalid command. Choose only from the following commands: {:?}.", arg.unwrap()),
-            InvalidGuessLengthErr => format!("The guessed word must have {} characters only.\n", WORD_LENGTH),
+            InvalidGuessLengthErr(len) => format!("The guessed word must have {} characters only.\n", len),
             NonAlphaGuessErr => "The guessed word must contain alphabetical characters only.\n".to_string(),
             NotEnglishGuessErr => "The guessed word is not a valid English word.\n".to_string(),
+            GameOverErr => "The game is already over.\n".to_string(),
+            HardModeMisplacedLetterErr(pos, letter) => format!(
+                "Hard mode: the {} letter must be {}.\n", 
+                utils::ordinal(*pos), letter.to_ascii_uppercase()
+            ),
+            HardModeMissingLetterErr(letter, 1) => format!(
+                "Hard mode: the guess must contain {}.\n", letter.to_ascii_uppercase()
+            ),
+            HardModeMissingLetterErr(letter, count) => format!(
+                "Hard mode: the guess must contain {} at least {} times.\n", 
+                letter.to_ascii_uppercase(), count
+            ),
+            UnknownArgumentErr(name) => format!("Unknown argument '{}'. Run with --help to see the options.", name),
+            MissingArgumentValueErr(name) => format!("The argument '{}' requires a value.", name),
+            InvalidWordLengthErr(len) => format!(
+                "The word length must be a number from {} to {}, got '{}'.", 
+                MIN_WORD_LENGTH, MAX_WORD_LENGTH, len
+            ),
+            InvalidGuessCountErr(count) => format!(
+                "The number of guesses must be a number from 1 to {} or 'unlimited', got '{}'.", 
+                GUESS_LIMIT, count
+            ),
+            DailyAlreadyPlayedErr(puzzle_number) => format!(
+                "You already played daily puzzle #{} today. Come back tomorrow!", puzzle_number
+            ),
+            NoDailyPuzzleErr => "There is no daily puzzle for today's date. Is the clock of your computer right?".to_string(),
+            InvalidSeedErr(seed) => format!(
+                "The seed must be a non-negative whole number, got '{}'.", seed
+            ),
+            InvalidPuzzleIdErr(id) => format!(
+                "'{}' is not a valid puzzle ID. Puzzle IDs look like '5-1203'.", id
+            ),
+            ConflictingArgumentsErr(first, second) => format!(
+                "The arguments '{}' and '{}' cannot be used together.", first, second
+            ),
+            InvalidBoardCountErr(count) => format!(
+                "The number of boards must be one of {:?}, got '{}'.", BOARD_COUNTS, count
+            ),
+            InvalidFeedbackErr(len) => format!(
+                "The feedback must have {} characters, each one g (green), y (yellow) or . (gray), e.g. 'gy..g'.\n", 
+                len
+            ),
+            NoMatchingWordErr => "No word in the list matches these clues.\n".to_string(),
+            InvalidModeErr(mode) => format!(
+                "Unknown mode '{}'. Choose from: {}.", mode, Mode::get_names().join(", ")
+            ),
+            InvalidWordListErr(name) => format!(
+                "Unknown word list '{}'. Choose from: {}.", name, WordList::get_names().join(", ")
+            ),
+            WordListReadErr(path, reason) => format!("Could not read the word list {}: {}.", path, reason),
+            InvalidWordListFileErr(path, issues) => format!(
+                "The word list {} cannot be used:{}", path, list_some(issues)
+            ),
+            AnswersNotAllowedErr(words) => format!(
+                "These answers are missing from the allowed guesses, so they could never be guessed:{}", 
+                list_some(words)
+            ),
+            NotEnoughAnswersErr(count) => format!(
+                "The answer list needs at least {} words for this game.", count
+            ),
+            UnknownWordPackErr(name, available) => format!(
+                "There is no word pack named '{}'. The word packs are: {}.", name, available.join(", ")
+            ),
+            WordPackLengthErr(name, len) => format!(
+                "The word pack '{}' only has {}-letter words.", name, len
+            ),
+            InvalidPackChoiceErr(count) => format!(
+                "Enter a number from 1 to {}, or nothing to keep the current words.\n", count
+            ),
+            CorruptHighScoresErr(Some(backup_path), issues) => format!(
+                "The high score file was damaged, so only the scores that could be read were kept. \
+                The old file was saved as {}. What was wrong:{}", backup_path, list_some(issues)
+            ),
+            CorruptHighScoresErr(None, issues) => format!(
+                "The high score file was damaged, so only the scores that could be read were kept. \
+                What was wrong:{}", list_some(issues)
+            ),
+            NewerHighScoresErr(version) => format!(
+                "The high score file was written by a newer version of the game (version {}), \
+                so it is left as it is and the high scores of this session are not kept.", version
+            ),
+            InvalidSavedGameErr => "The saved round cannot be resumed, so a new one is started instead.".to_string(),
+            InvalidSavedGameChoiceErr(count) => format!(
+                "Enter a number from 1 to {}, or nothing to start a new round.\n", count
+            ),
         }
     }
 }
+
+// Lists the first few items, one per line, and how many more there are.
+fn list_some(items: &[String]) -> String {
+    const MAX_LISTED: usize = 10;
+
+    let mut list: String = items.iter()
+        .take(MAX_LISTED)
+        .map(|item| format!("\n  {}", item))
+        .collect();
+
+    if items.len() > MAX_LISTED {
+        list.push_str(&format!("\n  ...and {} more", items.len() - MAX_LISTED));
+    }
+
+    list
+}
@@ -0,0 +1,485 @@
+// A single round of Wordle. The game owns the answer, the rules and every guess
+// made so far, and leaves reading input and printing to whoever drives it.
+//
+// In an adversarial ("Absurdle") game there is no answer up front. The game
+// keeps every word that is still possible, and answers each guess with the
+// feedback that leaves the most of them, only giving in when it has to.
+
+use std::collections::HashMap;
+
+use crate::constants::{WORD_LENGTH, MIN_WORD_LENGTH, MAX_WORD_LENGTH, MAX_GUESSES};
+use crate::err::AppError;
+use crate::scoring::{self, Feedback, Pattern};
+use crate::user_input;
+use crate::wordbank::WordBank;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Rules {
+    word_length: usize,
+    max_guesses: Option<usize>,
+    hard_mode: bool,
+}
+
+impl Rules {
+    // A `max_guesses` of `None` lets the player keep guessing until they win.
+    // In hard mode, every guess must make use of the hints revealed so far.
+    pub fn new(word_length: usize, max_guesses: Option<usize>, hard_mode: bool) -> Rules {
+        assert!((MIN_WORD_LENGTH..=MAX_WORD_LENGTH).contains(&word_length));
+        assert_ne!(Some(0), max_guesses);
+
+        Rules { word_length, max_guesses, hard_mode }
+    }
+
+    pub fn get_word_length(&self) -> usize {
+        self.word_length
+    }
+
+    pub fn get_max_guesses(&self) -> Option<usize> {
+        self.max_guesses
+    }
+
+    pub fn is_hard_mode(&self) -> bool {
+        self.hard_mode
+    }
+}
+
+impl Default for Rules {
+    fn default() -> Rules {
+        Rules::new(WORD_LENGTH, Some(MAX_GUESSES), false)
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum GameStatus {
+    InProgress,
+    Won,
+    Lost,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TurnOutcome {
+    feedback: Feedback,
+    status: GameStatus,
+}
+
+impl TurnOutcome {
+    pub fn get_feedback(&self) -> &Feedback {
+        &self.feedback
+    }
+
+    pub fn get_status(&self) -> GameStatus {
+        self.status
+    }
+}
+
+enum Answer {
+    Fixed(String),
+    // The words that match every feedback given so far, in word list order.
+    Adversarial(Vec<String>),
+}
+
+pub struct Game<'a> {
+    answer: Answer,
+    rules: Rules,
+    wordbank: &'a WordBank,
+    history: Vec<Feedback>,
+    status: GameStatus,
+}
+
+impl<'a> Game<'a> {
+    pub fn new(answer: String, rules: Rules, wordbank: &'a WordBank) -> Game<'a> {
+        assert_eq!(answer.to_lowercase(), answer);
+        assert_eq!(rules.word_length, answer.chars().count());
+        assert_eq!(rules.word_length, wordbank.get_word_length());
+
+        Game {
+            answer: Answer::Fixed(answer),
+            rules,
+            wordbank,
+            history: Vec::new(),
+            status: GameStatus::InProgress,
+        }
+    }
+
+    // Starts a game where any word of the answer list can still be the answer.
+    pub fn new_adversarial(rules: Rules, wordbank: &'a WordBank) -> Game<'a> {
+        assert_eq!(rules.word_length, wordbank.get_word_length());
+
+        Game {
+            answer: Answer::Adversarial(wordbank.get_unknown_words().clone()),
+            rules,
+            wordbank,
+            history: Vec::new(),
+            status: GameStatus::InProgress,
+        }
+    }
+
+    // Validates and scores the guess. A rejected guess does not use up a turn.
+    pub fn submit(&mut self, guess: &str) -> Result<TurnOutcome, AppError> {
+        if self.status != GameStatus::InProgress {
+            return Err(AppError::GameOverErr);
+        }
+
+        let mut guess = guess.to_string();
+
+        user_input::is_guess_valid(&mut guess, self.wordbank)?;
+
+        if self.rules.hard_mode {
+            user_input::is_guess_using_hints(&guess, &self.history)?;
+        }
+
+        let feedback = match &mut self.answer {
+            Answer::Fixed(answer) => scoring::score(&guess, answer),
+            Answer::Adversarial(candidates) => {
+                *candidates = keep_largest_group(&guess, candidates);
+                scoring::score(&guess, &candidates[0])
+            }
+        };
+
+        self.history.push(feedback.clone());
+
+        if feedback.is_win() {
+            self.status = GameStatus::Won;
+        } else if self.get_guesses_left() == Some(0) {
+            self.status = GameStatus::Lost;
+        }
+
+        Ok(TurnOutcome { feedback, status: self.status })
+    }
+
+    // In an adversarial game, this is one of the words that are still possible,
+    // which is the answer once the player has won.
+    pub fn get_answer(&self) -> &str {
+        match &self.answer {
+            Answer::Fixed(answer) => answer,
+            Answer::Adversarial(candidates) => &candidates[0],
+        }
+    }
+
+    pub fn is_adversarial(&self) -> bool {
+        matches!(self.answer, Answer::Adversarial(_))
+    }
+
+    pub fn get_rules(&self) -> Rules {
+        self.rules
+    }
+
+    pub fn get_status(&self) -> GameStatus {
+        self.status
+    }
+
+    pub fn get_history(&self) -> &Vec<Feedback> {
+        &self.history
+    }
+
+    pub fn get_guesses_left(&self) -> Option<usize> {
+        self.rules.max_guesses.map(|max| max - self.history.len())
+    }
+}
+
+// Several boards played with the same guesses, as in Dordle, Quordle or
+// Octordle. Each guess goes to every board that is not solved yet, so the
+// boards also share the guess budget of the rules.
+pub struct MultiGame<'a> {
+    boards: Vec<Game<'a>>,
+    rules: Rules,
+    wordbank: &'a WordBank,
+    guess_count: usize,
+}
+
+impl<'a> MultiGame<'a> {
+    pub fn new(answers: Vec<String>, rules: Rules, wordbank: &'a WordBank) -> MultiGame<'a> {
+        assert!(!answers.is_empty());
+
+        let boards = answers
+            .into_iter()
+            .map(|answer| Game::new(answer, rules, wordbank))
+            .collect();
+
+        MultiGame { boards, rules, wordbank, guess_count: 0 }
+    }
+
+    // Returns the outcome on each board, or `None` for the boards that were
+    // already solved. A guess rejected by any board is not played on any of them.
+    pub fn submit(&mut self, guess: &str) -> Result<Vec<Option<TurnOutcome>>, AppError> {
+        if self.get_status() != GameStatus::InProgress {
+            return Err(AppError::GameOverErr);
+        }
+
+        let mut guess = guess.to_string();
+
+        user_input::is_guess_valid(&mut guess, self.wordbank)?;
+
+        if self.rules.hard_mode {
+            for board in self.unsolved_boards() {
+                user_input::is_guess_using_hints(&guess, &board.history)?;
+            }
+        }
+
+        self.guess_count += 1;
+
+        let outcomes = self.boards
+            .iter_mut()
+            .map(|board| match board.status {
+                GameStatus::InProgress => Some(
+                    board.submit(&guess).expect("The guess was already checked.")
+                ),
+                _ => None,
+            })
+            .collect();
+
+        Ok(outcomes)
+    }
+
+    pub fn get_boards(&self) -> &Vec<Game<'a>> {
+        &self.boards
+    }
+
+    pub fn get_rules(&self) -> Rules {
+        self.rules
+    }
+
+    // Won once every board is solved, and lost as soon as one runs out of guesses.
+    pub fn get_status(&self) -> GameStatus {
+        let statuses: Vec<GameStatus> = self.boards.iter().map(|board| board.status).collect();
+
+        if statuses.iter().all(|&status| status == GameStatus::Won) {
+            GameStatus::Won
+        } else if statuses.contains(&GameStatus::Lost) {
+            GameStatus::Lost
+        } else {
+            GameStatus::InProgress
+        }
+    }
+
+    pub fn get_guess_count(&self) -> usize {
+        self.guess_count
+    }
+
+    pub fn get_guesses_left(&self) -> Option<usize> {
+        self.rules.max_guesses.map(|max| max - self.guess_count)
+    }
+
+    fn unsolved_boards(&self) -> impl Iterator<Item = &Game<'a>> {
+        self.boards.iter().filter(|board| board.status == GameStatus::InProgress)
+    }
+}
+
+// Groups the candidates by the feedback the guess would get, and keeps the
+// largest group. On a tie, the game avoids handing out the win, then keeps the
+// group with the lowest packed pattern so the same guesses always play out the
+// same way.
+fn keep_largest_group(guess: &str, candidates: &[String]) -> Vec<String> {
+    let patterns = scoring::score_patterns(guess, candidates);
+    let win_pattern = scoring::score(guess, guess).get_pattern();
+
+    let mut group_sizes: HashMap<Pattern, usize> = HashMap::new();
+
+    for &pattern in &patterns {
+        *group_sizes.entry(pattern).or_insert(0) += 1;
+    }
+
+    let (&kept_pattern, _) = group_sizes
+        .iter()
+        .max_by(|(&a, &size_a), (&b, &size_b)| {
+            size_a.cmp(&size_b)
+                  .then((b == win_pattern).cmp(&(a == win_pattern)))
+                  .then(b.cmp(&a))
+        })
+        .expect("There should always be a word left to choose from.");
+
+    candidates.iter()
+              .zip(patterns)
+              .filter(|&(_, pattern)| pattern == kept_pattern)
+              .map(|(candidate, _)| candidate.clone())
+              .collect()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_submit_win() {
+        let wordbank = WordBank::init();
+        let mut game = Game::new("hello".to_string(), Rules::default(), &wordbank);
+
+        let outcome = game.submit("shell").unwrap();
+        assert_eq!(GameStatus::InProgress, outcome.get_status());
+
+        let outcome = game.submit("HELLO").unwrap();
+        assert_eq!(GameStatus::Won, outcome.get_status());
+        assert!(outcome.get_feedback().is_win());
+
+        assert_eq!(2, game.get_history().len());
+        assert_eq!(Err(AppError::GameOverErr), game.submit("hello"));
+    }
+
+    #[test]
+    fn test_submit_loss() {
+        let wordbank = WordBank::init();
+        let mut game = Game::new("hello".to_string(), Rules::new(5, Some(2), false), &wordbank);
+
+        game.submit("catch").unwrap();
+        assert_eq!(Some(1), game.get_guesses_left());
+
+        let outcome = game.submit("zebra").unwrap();
+        assert_eq!(GameStatus::Lost, outcome.get_status());
+        assert_eq!(Some(0), game.get_guesses_left());
+    }
+
+    #[test]
+    fn test_invalid_guess_is_not_counted() {
+        let wordbank = WordBank::init();
+        let mut game = Game::new("hello".to_string(), Rules::default(), &wordbank);
+
+        assert_eq!(Err(AppError::InvalidGuessLengthErr(5)), game.submit("hell"));
+        assert_eq!(Err(AppError::NonAlphaGuessErr), game.submit("he11o"));
+        assert_eq!(Err(AppError::NotEnglishGuessErr), game.submit("zebrt"));
+
+        assert!(game.get_history().is_empty());
+        assert_eq!(Some(MAX_GUESSES), game.get_guesses_left());
+    }
+
+    #[test]
+    fn test_other_word_lengths() {
+        let wordbank = WordBank::init_with_length(7);
+        let mut game = Game::new("balance".to_string(), Rules::new(7, Some(MAX_GUESSES), false), &wordbank);
+
+        assert_eq!(Err(AppError::InvalidGuessLengthErr(7)), game.submit("hello"));
+
+        let outcome = game.submit("balance").unwrap();
+        assert_eq!(GameStatus::Won, outcome.get_status());
+        assert_eq!(7, outcome.get_feedback().get_letters().len());
+    }
+
+    #[test]
+    fn test_hard_mode() {
+        let wordbank = WordBank::init();
+        let mut game = Game::new("hello".to_string(), Rules::new(5, Some(MAX_GUESSES), true), &wordbank);
+
+        game.submit("shell").unwrap();
+
+        assert_eq!(Err(AppError::HardModeMisplacedLetterErr(4, 'l')), game.submit("hotel"));
+        assert_eq!(Err(AppError::HardModeMissingLetterErr('h', 1)), game.submit("lolly"));
+        assert_eq!(Err(AppError::HardModeMissingLetterErr('l', 2)), game.submit("whelp"));
+        assert_eq!(1, game.get_history().len());
+
+        assert_eq!(GameStatus::Won, game.submit("hello").unwrap().get_status());
+    }
+
+    #[test]
+    fn test_keep_largest_group() {
+        let candidates: Vec<String> = ["batch", "catch", "hatch", "hello", "zebra"]
+            .iter()
+            .map(|word| word.to_string())
+            .collect();
+
+        // "catch" wins against one word, but two others share one feedback.
+        assert_eq!(vec!["batch", "hatch"], keep_largest_group("catch", &candidates[..3]));
+        // The A of "zebra" is a lower pattern than the H of "hello".
+        assert_eq!(vec!["zebra"], keep_largest_group("catch", &candidates[3..]));
+        assert_eq!(vec!["catch"], keep_largest_group("catch", &candidates[1..2]));
+        // The win is only given when nothing else is left.
+        assert_eq!(vec!["hello"], keep_largest_group("catch", &[candidates[1].clone(), candidates[3].clone()]));
+    }
+
+    #[test]
+    fn test_adversarial_game() {
+        let wordbank = WordBank::init();
+        let mut game = Game::new_adversarial(Rules::new(5, None, false), &wordbank);
+
+        assert!(game.is_adversarial());
+
+        let mut guesses = 0;
+
+        while game.get_status() == GameStatus::InProgress {
+            // Always guess a word the game could still be thinking of.
+            let guess = game.get_answer().to_string();
+            let outcome = game.submit(&guess).unwrap();
+
+            for feedback in game.get_history() {
+                assert_eq!(*feedback, scoring::score(&feedback.get_guess(), game.get_answer()));
+            }
+
+            guesses += 1;
+            assert!(guesses <= wordbank.get_unknown_words().len());
+
+            if outcome.get_status() == GameStatus::Won {
+                assert_eq!(guess, game.get_answer());
+            }
+        }
+
+        assert!(guesses > 1);
+    }
+
+    #[test]
+    fn test_multi_game() {
+        let wordbank = WordBank::init();
+        let answers = vec!["hello".to_string(), "catch".to_string()];
+        let mut game = MultiGame::new(answers, Rules::new(5, Some(3), false), &wordbank);
+
+        assert_eq!(Err(AppError::NotEnglishGuessErr), game.submit("zebrt"));
+        assert_eq!(0, game.get_guess_count());
+
+        let outcomes = game.submit("catch").unwrap();
+        assert_eq!(Some(GameStatus::InProgress), outcomes[0].as_ref().map(|o| o.get_status()));
+        assert_eq!(Some(GameStatus::Won), outcomes[1].as_ref().map(|o| o.get_status()));
+        assert_eq!(GameStatus::InProgress, game.get_status());
+
+        // The solved board does not take any more guesses.
+        let outcomes = game.submit("shell").unwrap();
+        assert!(outcomes[1].is_none());
+        assert_eq!(1, game.get_boards()[1].get_history().len());
+        assert_eq!(Some(1), game.get_guesses_left());
+
+        game.submit("hello").unwrap();
+        assert_eq!(GameStatus::Won, game.get_status());
+        assert_eq!(Err(AppError::GameOverErr), game.submit("hello"));
+    }
+
+    #[test]
+    fn test_multi_game_loss() {
+        let wordbank = WordBank::init();
+        let answers = vec!["hello".to_string(), "catch".to_string()];
+        let mut game = MultiGame::new(answers, Rules::new(5, Some(1), false), &wordbank);
+
+        game.submit("hello").unwrap();
+
+        assert_eq!(GameStatus::Lost, game.get_status());
+        assert_eq!(Some(0), game.get_guesses_left());
+    }
+
+    #[test]
+    fn test_multi_game_hard_mode() {
+        let wordbank = WordBank::init();
+        let answers = vec!["hello".to_string(), "zebra".to_string()];
+        let mut game = MultiGame::new(answers, Rules::new(5, Some(9), true), &wordbank);
+
+        game.submit("shell").unwrap();
+
+        // "cache" uses the hint of the second board, but not those of the first.
+        assert_eq!(Err(AppError::HardModeMisplacedLetterErr(4, 'l')), game.submit("cache"));
+
+        game.submit("hello").unwrap();
+
+        // The first board is solved, so only the hints of the second one are left.
+        assert_eq!(Err(AppError::HardModeMisplacedLetterErr(2, 'e')), game.submit("these"));
+        assert_eq!(2, game.get_guess_count());
+    }
+
+    #[test]
+    fn test_unlimited_guesses() {
+        let wordbank = WordBank::init();
+        let mut game = Game::new("hello".to_string(), Rules::new(5, None, false), &wordbank);
+
+        for _ in 0..(MAX_GUESSES * 2) {
+            let outcome = game.submit("catch").unwrap();
+            assert_eq!(GameStatus::InProgress, outcome.get_status());
+        }
+
+        assert_eq!(None, game.get_guesses_left());
+        assert_eq!(GameStatus::Won, game.submit("hello").unwrap().get_status());
+    }
+}
@@ -1,92 +1,125 @@
-use std::io::prelude::*;
-use std::io::ErrorKind;
-use std::fs::{self, File};
-use std::env;
+// The high score file starts with a header that tells its version:
+//
+//     # wordle_clone high scores, version 2
+//     812 400 212 200 0 abcde
+//     640 300 240 100 0 fghij
+//
+// Each line is the total score, its guess, time, difficulty and hard mode
+// points (see score_model), then the username, which may contain spaces.
+// Version 1 files have "score username" lines, and files written before the
+// header existed have "username score" lines, where the score is the number of
+// guesses left plus one, out of 5 guesses for the latter. Both are still read.
+// A file that cannot be read completely is backed up, and the scores that could
+// be read are kept.
 
-#[derive(Debug)]
+use std::cmp::Reverse;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::Local;
+
+use crate::constants::GUESS_LIMIT;
+use crate::err::AppError;
+use crate::score_model::{self, ScoreBreakdown};
+use crate::store;
+use crate::utils;
+
+const HIGHSCORE_HEADER: &str = "# wordle_clone high scores, version ";
+const HIGHSCORE_VERSION: u32 = 2;
+const MAX_HIGH_SCORES: usize = 5;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
 pub struct UserScore {
     username: String,
-    score: u8
+    breakdown: ScoreBreakdown,
 }
 
 impl UserScore {
-    pub fn new(username: String, score: u8) -> UserScore {
-        UserScore { username, score }
+    pub fn new(username: String, breakdown: ScoreBreakdown) -> UserScore {
+        UserScore { username, breakdown }
     }
 
     pub fn get_username(&self) -> &String {
         &self.username
     }
 
-    pub fn get_score(&self) -> u8 {
-        self.score
+    pub fn get_score(&self) -> u32 {
+        self.breakdown.get_total()
+    }
+
+    pub fn get_breakdown(&self) -> &ScoreBreakdown {
+        &self.breakdown
     }
 }
 
 #[derive(Debug)]
 pub struct HighScores {
     high_scores: Vec<UserScore>,
-    highscore_text_path: std::path::PathBuf
+    highscore_text_path: PathBuf,
+    // Why the file had to be recovered or cannot be used, if it did.
+    warning: Option<AppError>,
+    // A file written by a newer version is never written over.
+    read_only: bool,
 }
 
 impl HighScores {
     pub fn init() -> HighScores {
-        let mut curr_exe_dir = env::current_exe()
-        .expect("Failed to get the path of the program's executable.");
-        
-        curr_exe_dir.pop();
-        curr_exe_dir.push("wc_resources");
-
-        let game_resources_dir_path = curr_exe_dir.clone();
-
-        if !game_resources_dir_path.is_dir() {
-            fs::create_dir(game_resources_dir_path)
-                .expect("Failed to create a directory for the game's resources.");
-        }
-
-        let mut highscore_text_path = curr_exe_dir.clone();
+        let mut highscore_text_path = utils::get_data_dir();
 
         highscore_text_path.push("highscore.txt");
 
         let high_scores_str = if highscore_text_path.is_file() {
-            let high_scores_str = fs::read_to_string(highscore_text_path.clone())
-                .expect("Failed to read highscore.txt");
-            
-            if high_scores_str.is_empty() {
-                return HighScores { high_scores: Vec::new(), highscore_text_path };
+            match fs::read_to_string(&highscore_text_path) {
+                Ok(high_scores_str) => high_scores_str,
+                // The file is not even text, so none of it can be kept.
+                Err(err) => {
+                    let issues = vec![err.to_string()];
+                    return HighScores::recover(Vec::new(), issues, highscore_text_path);
+                }
             }
-
-            high_scores_str
         } else {
-            fs::File::create(highscore_text_path.clone())
-                .expect("Failed to create new file highscore.txt");
-            
-            return HighScores { high_scores: Vec::new(), highscore_text_path };
+            // The file is only written once there is a score to keep.
+            return HighScores { high_scores: Vec::new(), highscore_text_path, warning: None, read_only: false };
         };
-        
-        
-        let mut high_scores_clean: Vec<UserScore> = Vec::new();
-        let high_scores_raw: Vec<Vec<&str>> = high_scores_str
-            .lines()
-            .map(|line| line.split_ascii_whitespace().collect())
-            .collect();
-
-
-        for high_score in high_scores_raw {
-            if high_score.len() != 2 {
-                panic!("The high score {:?} does not have two elements only.", high_score);
-            }
 
-            let name = high_score[0].to_string();
-            let score: u8 = high_score[1]
-                .trim()
-                .parse()
-                .expect("Cannot parse one of the scores in highscore.txt");
+        if let Some(version) = get_newer_version(&high_scores_str) {
+            return HighScores {
+                high_scores: Vec::new(),
+                highscore_text_path,
+                warning: Some(AppError::NewerHighScoresErr(version)),
+                read_only: true,
+            };
+        }
+
+        let (high_scores, issues) = parse_high_scores(&high_scores_str);
 
-            high_scores_clean.push(UserScore::new(name, score));
+        if !issues.is_empty() {
+            return HighScores::recover(high_scores, issues, highscore_text_path);
         }
 
-        HighScores { high_scores: high_scores_clean, highscore_text_path }
+        HighScores { high_scores, highscore_text_path, warning: None, read_only: false }
+    }
+
+    // Backs up the damaged file, then replaces it with the scores that could
+    // be read.
+    fn recover(high_scores: Vec<UserScore>, issues: Vec<String>, highscore_text_path: PathBuf) -> HighScores {
+        let backup_path = get_backup_path(&highscore_text_path);
+
+        let backup_path = match fs::copy(&highscore_text_path, &backup_path) {
+            Ok(_) => Some(backup_path.display().to_string()),
+            Err(_) => None,
+        };
+        let warning = AppError::CorruptHighScoresErr(backup_path, issues);
+
+        if let Err(err) = store::update_file(&highscore_text_path, |_| to_text(&high_scores)) {
+            eprintln!("IO Error: {}", err);
+        }
+
+        HighScores { high_scores, highscore_text_path, warning: Some(warning), read_only: false }
+    }
+
+    pub fn get_warning(&self) -> Option<&AppError> {
+        self.warning.as_ref()
     }
 
     pub fn get_high_scores(&self) -> &Vec<UserScore> {
@@ -94,57 +127,287 @@ impl HighScores {
     }
 
     pub fn try_insert_new_score(&mut self, new_score: UserScore) {
-        let mut pos: Option<usize> = None;
-
-        if self.high_scores.is_empty() {
-            self.high_scores.push(new_score);
-            self.update_high_scores();
+        if self.read_only {
+            insert_score(&mut self.high_scores, new_score);
             return;
         }
 
-        for (i, userscore) in self.high_scores.iter().enumerate() {
-            if new_score.score > userscore.score { 
-                pos = Some(i); 
-                break;
-            }
+        self.high_scores = store::update_entries(
+            &self.highscore_text_path,
+            &self.high_scores,
+            |high_scores_str| parse_high_scores(high_scores_str).0,
+            |high_scores| insert_score(high_scores, new_score.clone()),
+            to_text,
+        );
+    }
+}
+
+// Keeps the best scores, best first. A new score goes after the equal ones.
+fn insert_score(high_scores: &mut Vec<UserScore>, new_score: UserScore) {
+    let pos = high_scores.iter()
+        .position(|userscore| new_score.get_score() > userscore.get_score())
+        .unwrap_or(high_scores.len());
+
+    high_scores.insert(pos, new_score);
+    high_scores.truncate(MAX_HIGH_SCORES);
+}
+
+fn to_text(high_scores: &[UserScore]) -> String {
+    let mut buf = format!("{}{}\n", HIGHSCORE_HEADER, HIGHSCORE_VERSION);
+
+    for userscore in high_scores {
+        let breakdown = userscore.get_breakdown();
+
+        buf.push_str(&format!(
+            "{} {} {} {} {} {}\n", 
+            breakdown.get_total(), breakdown.get_guess_points(), breakdown.get_time_points(), 
+            breakdown.get_difficulty_points(), breakdown.get_hard_mode_points(), userscore.get_username()
+        ));
+    }
+
+    buf
+}
+
+// The version in the header, if it is one this version of the game does not
+// know yet. Anything else that is wrong with the header is damage.
+fn get_newer_version(text: &str) -> Option<u32> {
+    text.lines()
+        .next()?
+        .strip_prefix(HIGHSCORE_HEADER)?
+        .trim()
+        .parse::<u32>()
+        .ok()
+        .filter(|&version| version > HIGHSCORE_VERSION)
+}
+
+// Returns the scores that could be read, best first, and what was wrong with
+// the rest of the file.
+fn parse_high_scores(text: &str) -> (Vec<UserScore>, Vec<String>) {
+    let mut high_scores: Vec<UserScore> = Vec::new();
+    let mut issues: Vec<String> = Vec::new();
+
+    let mut lines = text.lines().enumerate().peekable();
+
+    let version = match lines.peek() {
+        Some((_, line)) if line.starts_with(HIGHSCORE_HEADER) => {
+            let version = line[HIGHSCORE_HEADER.len()..].trim();
+
+            let version = match version.parse::<u32>() {
+                Ok(version @ 1..=HIGHSCORE_VERSION) => version,
+                _ => {
+                    issues.push(format!("line 1: unknown version '{}'", version));
+                    HIGHSCORE_VERSION
+                }
+            };
+
+            lines.next();
+            version
         }
-        
-        if let Some(i) = pos { 
-            self.high_scores.insert(i, new_score); 
-        } else { 
-            self.high_scores.push(new_score); 
+        _ => 0,
+    };
+
+    for (i, line) in lines {
+        let line_number = i + 1;
+
+        if line.trim().is_empty() {
+            continue;
         }
 
-        if self.high_scores.len() > 5 {
-            self.high_scores.pop();
+        let userscore = match version {
+            0 => line.trim()
+                .rsplit_once(' ')
+                .filter(|(name, _)| !name.contains(' '))
+                .and_then(|(name, score)| parse_legacy_score(name, score, version)),
+            1 => line.split_once(' ').and_then(|(score, name)| parse_legacy_score(name, score, version)),
+            _ => parse_score(line),
+        };
+
+        match userscore {
+            Some(userscore) => high_scores.push(userscore),
+            None => issues.push(format!("line {}: '{}' is not a valid high score", line_number, line)),
         }
+    }
+
+    // The file may have been edited by hand, so the order is not trusted.
+    high_scores.sort_by_key(|userscore| Reverse(userscore.get_score()));
 
-        self.update_high_scores();
+    if high_scores.len() > MAX_HIGH_SCORES {
+        issues.push(format!(
+            "there are {} high scores instead of at most {}", high_scores.len(), MAX_HIGH_SCORES
+        ));
+        high_scores.truncate(MAX_HIGH_SCORES);
     }
 
-    fn update_high_scores(&self) {
-        let mut highscore_text = File::create(self.highscore_text_path.clone())
-            .expect("'highscore.txt' is not a valid file path");
+    (high_scores, issues)
+}
 
-        let mut buf = String::new();
+// "total guess time difficulty hard username", where the total must add up.
+fn parse_score(line: &str) -> Option<UserScore> {
+    let fields: Vec<&str> = line.splitn(6, ' ').collect();
 
-        for userscore in &self.high_scores {
-            buf.push_str(format!(
-                "{} {}\n", 
-                userscore.get_username(), userscore.get_score()
-            ).as_str());
+    if fields.len() != 6 || fields[5].trim().is_empty() {
+        return None;
+    }
+
+    let points = fields[..5].iter()
+        .map(|field| field.parse::<u32>().ok())
+        .collect::<Option<Vec<u32>>>()?;
+    let breakdown = ScoreBreakdown::new(points[1], points[2], points[3], points[4]);
+
+    if breakdown.get_total() != points[0] || !breakdown.is_possible() {
+        return None;
+    }
+
+    Some(UserScore::new(fields[5].to_string(), breakdown))
+}
+
+// Each version counted the guesses left out of its own guess budget.
+fn parse_legacy_score(name: &str, score: &str, version: u32) -> Option<UserScore> {
+    let score = score.trim().parse::<u8>().ok()?;
+    let max_score = if version == 0 { score_model::UNVERSIONED_MAX_GUESSES } else { GUESS_LIMIT };
+
+    if name.trim().is_empty() || !(1..=max_score as u8).contains(&score) {
+        return None;
+    }
+
+    let breakdown = if version == 0 {
+        score_model::from_unversioned_score(score)
+    } else {
+        score_model::from_version_1_score(score)
+    };
+
+    Some(UserScore::new(name.to_string(), breakdown))
+}
+
+// e.g. highscore.txt -> highscore-20221203-142501.bak
+fn get_backup_path(highscore_text_path: &Path) -> PathBuf {
+    let stem = highscore_text_path.file_stem().unwrap_or_default().to_string_lossy();
+
+    highscore_text_path.with_file_name(format!(
+        "{}-{}.bak", stem, Local::now().format("%Y%m%d-%H%M%S")
+    ))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Scores worth as much as version 1 ones, e.g. 6 is 600 points.
+    fn scores(scores: &[(&str, u8)]) -> Vec<UserScore> {
+        scores.iter()
+            .map(|&(name, score)| UserScore::new(name.to_string(), score_model::from_version_1_score(score)))
+            .collect()
+    }
+
+    #[test]
+    fn test_insert_score() {
+        let mut high_scores = scores(&[("abcde", 6), ("fghij", 3)]);
+
+        insert_score(&mut high_scores, scores(&[("klmno", 3)]).remove(0));
+        insert_score(&mut high_scores, scores(&[("pqrst", 4)]).remove(0));
+
+        assert_eq!(scores(&[("abcde", 6), ("pqrst", 4), ("fghij", 3), ("klmno", 3)]), high_scores);
+
+        insert_score(&mut high_scores, scores(&[("uvwxy", 1)]).remove(0));
+        insert_score(&mut high_scores, scores(&[("zzzzz", 1)]).remove(0));
+
+        assert_eq!(5, high_scores.len());
+        assert_eq!("uvwxy", high_scores[4].get_username());
+    }
+
+    #[test]
+    fn test_parse_high_scores() {
+        let high_scores = scores(&[("abcde", 6), ("a b c", 4), ("fghij", 1)]);
+        let text = to_text(&high_scores);
+
+        assert!(text.starts_with("# wordle_clone high scores, version 2\n600 600 0 0 0 abcde\n"));
+        assert_eq!((high_scores, Vec::<String>::new()), parse_high_scores(&text));
+        assert_eq!((Vec::new(), Vec::<String>::new()), parse_high_scores(""));
+    }
+
+    #[test]
+    fn test_parse_legacy_high_scores() {
+        assert_eq!(
+            (scores(&[("abcde", 6), ("fghij", 4)]), Vec::<String>::new()),
+            parse_high_scores("abcde 5\nfghij 3")
+        );
+
+        let (_, issues) = parse_high_scores("abcde 6\n");
+
+        assert_eq!(vec!["line 1: 'abcde 6' is not a valid high score"], issues);
+    }
+
+    #[test]
+    fn test_parse_version_1_high_scores() {
+        assert_eq!(
+            (scores(&[("a b c", 5), ("fghij", 2)]), Vec::<String>::new()),
+            parse_high_scores("# wordle_clone high scores, version 1\n2 fghij\n5 a b c\n")
+        );
+        assert_eq!(
+            (scores(&[("klmno", 6)]), Vec::<String>::new()),
+            parse_high_scores("# wordle_clone high scores, version 1\n20 klmno\n")
+        );
+    }
+
+    #[test]
+    fn test_parse_score() {
+        let breakdown = ScoreBreakdown::new(400, 212, 120, 146);
+
+        assert_eq!(
+            Some(UserScore::new("a b c".to_string(), breakdown)), 
+            parse_score("878 400 212 120 146 a b c")
+        );
+
+        for line in ["877 400 212 120 146 abcde", "2000 2000 0 0 0 abcde", "400 400 0 0 0", "x 400 0 0 0 abcde"] {
+            assert_eq!(None, parse_score(line));
         }
+    }
 
-        // Removes the last newline char.
-        buf.pop();
-        
-        let buf = buf.as_bytes();
+    #[test]
+    fn test_parse_corrupt_high_scores() {
+        let (high_scores, issues) = parse_high_scores(
+            "# wordle_clone high scores, version 1\n3 abcde\nlots fghij\n\n6 klmno\n0 zeros\n"
+        );
 
-        while let Err(err) = highscore_text.write(buf) {
-            match err.kind() {
-                ErrorKind::Interrupted => continue,
-                _ => eprintln!("IO Error: {}", err),
-            }
+        assert_eq!(scores(&[("klmno", 6), ("abcde", 3)]), high_scores);
+        assert_eq!(
+            vec![
+                "line 3: 'lots fghij' is not a valid high score",
+                "line 6: '0 zeros' is not a valid high score",
+            ],
+            issues
+        );
+
+        let (high_scores, issues) = parse_high_scores("a b c 5\nabcde 3\nxyz");
+
+        assert_eq!(scores(&[("abcde", 4)]), high_scores);
+        assert_eq!(2, issues.len());
+
+        let (_, issues) = parse_high_scores("# wordle_clone high scores, version x\n3 abcde\n");
+
+        assert_eq!(
+            vec!["line 1: unknown version 'x'", "line 2: '3 abcde' is not a valid high score"],
+            issues
+        );
+    }
+
+    #[test]
+    fn test_get_newer_version() {
+        assert_eq!(Some(9), get_newer_version("# wordle_clone high scores, version 9\n3 abcde\n"));
+
+        for text in ["# wordle_clone high scores, version 2\n", "# wordle_clone high scores, version x\n", "abcde 3\n", ""] {
+            assert_eq!(None, get_newer_version(text));
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_parse_too_many_high_scores() {
+        let (high_scores, issues) = parse_high_scores(
+            "# wordle_clone high scores, version 1\n1 a\n2 b\n3 c\n4 d\n5 e\n6 f\n"
+        );
+
+        assert_eq!(scores(&[("f", 6), ("e", 5), ("d", 4), ("c", 3), ("b", 2)]), high_scores);
+        assert_eq!(vec!["there are 6 high scores instead of at most 5"], issues);
+    }
+}
@@ -1,5 +1,17 @@
 pub mod wordbank;
+pub mod wordpack;
 pub mod user_input;
+pub mod scoring;
+pub mod game;
+pub mod cli;
+pub mod daily;
+pub mod puzzle;
+pub mod save;
+pub mod score_model;
+pub mod share;
+pub mod stats;
+pub mod store;
+pub mod solver;
 pub mod display;
 pub mod constants;
 pub mod err;
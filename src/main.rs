@@ -1,71 +1,621 @@
 // Wordle made in Rust. (wow very original!)
 
 extern crate colored;
+extern crate rand;
 
+use std::env;
+use std::mem;
 use std::process;
 
-use wordle_clone::wordbank::{WordBank};
-use wordle_clone::user_input::{self, IfWinner};
-use wordle_clone::display::{self, Display, GuessColorMapping};
-use wordle_clone::constants::MAX_GUESSES;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use wordle_clone::wordbank::{WordBank, WordList};
+use wordle_clone::wordpack;
+use wordle_clone::user_input;
+use wordle_clone::display::{self, Display, MultiDisplay};
+use wordle_clone::game::{Game, GameStatus, MultiGame, Rules, TurnOutcome};
 use wordle_clone::highscore::{HighScores, UserScore};
+use wordle_clone::daily::{self, DailyResult, DailyResults};
+use wordle_clone::puzzle::PuzzleId;
+use wordle_clone::save::SavedGame;
+use wordle_clone::score_model::{self, Stopwatch};
+use wordle_clone::share;
+use wordle_clone::stats::{PlayerStats, Stats};
+use wordle_clone::solver::{self, Guesser, Strategy};
+use wordle_clone::cli::{self, Command, Mode, Selection};
+use wordle_clone::err::AppError;
 use wordle_clone::utils;
 
 fn main() {
+    let options = cli::parse_args(env::args().skip(1)).unwrap_or_else(|err| {
+        eprintln!("{}", err.to_str::<()>(None));
+        process::exit(2);
+    });
+
+    if !options.is_color_enabled() {
+        colored::control::set_override(false);
+    }
+
+    if let Some(data_dir) = options.get_data_dir() {
+        utils::set_dir_override(data_dir.clone());
+    }
+
+    match options.get_command() {
+        Command::ShowHelp => {
+            println!("{}", cli::get_usage());
+            process::exit(0);
+        }
+        Command::ShowVersion => {
+            println!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"));
+            process::exit(0);
+        }
+        Command::ShowStats => {
+            let highscore = HighScores::init();
+
+            if let Some(warning) = highscore.get_warning() {
+                eprintln!("{}\n", warning.to_str::<()>(None));
+            }
+
+            display::display_high_score(highscore.get_high_scores());
+            display::display_stats_summary(Stats::init().get_players());
+            process::exit(0);
+        }
+        Command::Play => (),
+    }
+
+    let mut word_list = options.get_word_list().clone();
+
+    // A word pack has its own word length, unless another one is asked for.
+    let word_length = match &word_list {
+        WordList::Pack(name) if !options.is_word_length_set() => wordpack::find_pack(name)
+            .map(|pack| pack.get_word_length())
+            .unwrap_or_else(|err| {
+                eprintln!("{}", err.to_str::<()>(None));
+                process::exit(2);
+            }),
+        _ => options.get_word_length(),
+    };
+
+    let mut rules = Rules::new(word_length, options.get_max_guesses(), options.is_hard_mode());
+
+    let mut wordbank = WordBank::init_with_list(rules.get_word_length(), &word_list)
+        .unwrap_or_else(|err| {
+            eprintln!("{}", err.to_str::<()>(None));
+            process::exit(2);
+        });
+
+    if let Err(err) = check_enough_answers(options.get_mode(), &wordbank) {
+        eprintln!("{}", err.to_str::<()>(None));
+        process::exit(2);
+    }
+
     utils::clear_screen();
 
-    let mut display: Display = Display::init();
-    let wordbank = WordBank::init();
     let mut highscore = HighScores::init();
-                           
-    let mut winner: IfWinner = false;
+    let mut daily_results = DailyResults::init();
+    let mut stats = Stats::init();
+
+    // Only the daily puzzle depends on the date.
+    let mut puzzle_number = match options.get_selection() {
+        Selection::Daily => daily::get_puzzle_number(daily::get_today()).unwrap_or_else(|| {
+            eprintln!("{}", AppError::NoDailyPuzzleErr.to_str::<()>(None));
+            process::exit(2);
+        }),
+        _ => 0,
+    };
+
+    // With a seed, every round of the session is reproducible, not just the first.
+    let mut rng = match options.get_selection() {
+        Selection::Seed(seed) => StdRng::seed_from_u64(seed),
+        _ => StdRng::from_entropy(),
+    };
+
+    // The daily puzzle and puzzle IDs always use the common list.
+    let can_pick_pack = !matches!(options.get_selection(), Selection::Daily | Selection::Puzzle(_));
 
-    let mut guesses_left = MAX_GUESSES;
-    let unknown_word: String = wordbank.get_random_word_in_unknown_words();
+    let mut mode = options.get_mode();
+    // The daily puzzle and a given puzzle ID only decide the first round.
+    let mut selection = options.get_selection();
 
-    let cmd: char = display::display_start_screen(highscore.get_high_scores());
+    let saved_games = SavedGame::load_all();
+    let mut resumed: Option<SavedGame> = None;
+    // What the session goes back to once a resumed round is over.
+    let mut session_settings: Option<(Mode, Rules, WordBank, WordList)> = None;
+
+    let cmd: char = display::display_start_screen(
+        highscore.get_high_scores(), stats.get_players(), can_pick_pack, &saved_games,
+        &highscore.get_warning().into_iter().collect::<Vec<&AppError>>()
+    );
     match cmd {
-        'H' => display::display_help(),
+        'H' => display::display_help(rules),
+        'R' => {
+            // Every player may have a saved round to pick from.
+            let choice = match saved_games.len() {
+                1 => Some(0),
+                _ => display::display_saved_games(&saved_games),
+            };
+
+            if let Some(saved_game) = choice.map(|i| saved_games[i].clone()) {
+                let saved_rules = saved_game.get_rules();
+
+                // The round goes on with the words and rules it was started with.
+                match WordBank::init_with_list(saved_rules.get_word_length(), saved_game.get_word_list()) {
+                    Ok(saved_wordbank) => {
+                        session_settings = Some((
+                            mode,
+                            rules,
+                            mem::replace(&mut wordbank, saved_wordbank),
+                            mem::replace(&mut word_list, saved_game.get_word_list().clone()),
+                        ));
+                        rules = saved_rules;
+                        mode = if saved_game.is_adversarial() { Mode::Absurdle } else { Mode::Classic };
+                        selection = Selection::Random;
+
+                        if let Some(saved_puzzle_number) = saved_game.get_puzzle_number() {
+                            puzzle_number = saved_puzzle_number;
+                            selection = Selection::Daily;
+                        }
+
+                        resumed = Some(saved_game);
+                    }
+                    Err(err) => {
+                        eprintln!("{}", err.to_str::<()>(None));
+                        eprintln!("{}", AppError::InvalidSavedGameErr.to_str::<()>(None));
+                        saved_game.remove();
+                        utils::sleep_sec(2);
+                    }
+                }
+            }
+        }
+        'W' => {
+            let (packs, errors) = wordpack::load_packs();
+
+            if let Some(pack) = display::display_word_packs(&packs, &errors).map(|i| &packs[i]) {
+                let pack_wordbank = WordBank::init_with_pack(pack);
+
+                match check_enough_answers(mode, &pack_wordbank) {
+                    Ok(()) => {
+                        rules = Rules::new(
+                            pack.get_word_length(), options.get_max_guesses(), options.is_hard_mode()
+                        );
+                        wordbank = pack_wordbank;
+                        word_list = WordList::Pack(pack.get_name().clone());
+                    }
+                    Err(err) => {
+                        eprintln!("{}", err.to_str::<()>(None));
+                        utils::sleep_sec(2);
+                    }
+                }
+            }
+        }
         'Q' => process::exit(0),
         _   => (),
     }
 
-    let username = user_input::get_username();
-    
+    // In reverse mode the player only gives feedback, so there is no score to
+    // keep and no need for a username.
+    if mode == Mode::Reverse {
+        play_reverse_games(rules, &wordbank);
+    }
+
+    let username = match &resumed {
+        Some(saved_game) => saved_game.get_username().clone(),
+        None => user_input::get_username(),
+    };
+
+    loop {
+        // Only reachable after a resumed round, which knows the username.
+        if mode == Mode::Reverse {
+            play_reverse_games(rules, &wordbank);
+        }
+
+        // Multi-board games have their own boards, and do not count towards the
+        // statistics or the high scores.
+        if let Mode::MultiBoard(board_count) = mode {
+            let answers = wordbank.get_words_with_rng(&mut rng, board_count);
+
+            play_multi_board_game(answers, rules, &wordbank, stats.get_player_stats(&username));
+            continue;
+        }
+
+        // A saved round that cannot be replayed is dropped for a new one. A
+        // resumed round keeps its save, e.g. with the time it was started.
+        let resumed_round = resumed.take().and_then(|saved_game| {
+            match saved_game.restore(&wordbank) {
+                Ok((game, display)) => Some((game, display, saved_game)),
+                Err(err) => {
+                    eprintln!("{}", err.to_str::<()>(None));
+                    saved_game.remove();
+                    None
+                }
+            }
+        });
+
+        let daily_puzzle_number = if selection == Selection::Daily { Some(puzzle_number) } else { None };
+
+        let (mut game, mut display, mut saved_game) = match resumed_round {
+            Some(round) => round,
+            None => {
+                let game = match mode {
+                    Mode::MultiBoard(_) | Mode::Reverse => unreachable!(),
+                    Mode::Absurdle => Game::new_adversarial(rules, &wordbank),
+                    Mode::Classic => {
+                        let answer = match selection {
+                            Selection::Random | Selection::Seed(_) => wordbank.get_word_with_rng(&mut rng),
+                            Selection::Daily => {
+                                if daily_results.get_result(puzzle_number, rules.get_word_length(), &username).is_some() {
+                                    eprintln!("{}", AppError::DailyAlreadyPlayedErr(puzzle_number).to_str::<()>(None));
+                                    process::exit(0);
+                                }
+
+                                daily::get_daily_word(&wordbank, puzzle_number)
+                            }
+                            Selection::Puzzle(id) => id.get_answer(&wordbank).unwrap_or_else(|err| {
+                                eprintln!("{}", err.to_str::<()>(None));
+                                process::exit(2);
+                            }),
+                        };
+
+                        Game::new(answer, rules, &wordbank)
+                    }
+                };
+
+                let saved_game = SavedGame::new(username.clone(), &game, word_list.clone(), daily_puzzle_number);
+
+                (game, Display::init(rules), saved_game)
+            }
+        };
+
+        if game.is_adversarial() {
+            display.set_title("Absurdle".to_string());
+        } else if selection == Selection::Daily {
+            display.set_title(format!("Daily puzzle #{}", puzzle_number));
+        }
+
+        let stopwatch = Stopwatch::start_at(saved_game.get_started());
+
+        play_round(&mut game, &mut display, &wordbank, &mut saved_game);
+
+        // An Absurdle answer is only one of the words left, so it has no ID, and
+        // IDs only point into the common list.
+        let puzzle_id = if game.is_adversarial() || word_list != WordList::Common {
+            None
+        } else {
+            PuzzleId::of(&wordbank, game.get_answer())
+        };
+
+        let winner = game.get_status() == GameStatus::Won;
+        let guesses = if winner { Some(game.get_history().len()) } else { None };
+
+        // Practice games without a guess budget are always won, so they are left
+        // out of the statistics, and so are Absurdle games. Daily puzzles are
+        // kept with the daily results instead.
+        let counts_for_stats = rules.get_max_guesses().is_some() && !game.is_adversarial();
+
+        if counts_for_stats && selection != Selection::Daily {
+            stats.record_game(&username, guesses);
+        }
+
+        // Daily puzzles, practice and Absurdle games, and rounds played with
+        // hints, do not count towards the high scores.
+        let score = if selection == Selection::Daily {
+            daily_results.insert_result(DailyResult::new(
+                puzzle_number, rules.get_word_length(), username.clone(), guesses
+            ));
+            None
+        } else if let (Some(guesses), true) = (guesses, counts_for_stats && !saved_game.has_used_hints()) {
+            let difficulty = score_model::get_answer_difficulty(game.get_answer(), wordbank.get_unknown_words());
+            let score = score_model::score_round(
+                guesses, stopwatch.get_elapsed(), rules.is_hard_mode(), difficulty
+            );
+
+            highscore.try_insert_new_score(UserScore::new(username.clone(), score));
+            Some(score)
+        } else {
+            None
+        };
+
+        display::display_end_screen(winner, &[game.get_answer()], puzzle_id);
+
+        if let Some(score) = score {
+            display::display_score(&score);
+        } else if winner && counts_for_stats && saved_game.has_used_hints() && selection != Selection::Daily {
+            println!("\nYou used hints, so this round does not count towards the high scores.");
+        }
+
+        if let (Some(player_stats), true) = (stats.get_player_stats(&username), counts_for_stats) {
+            display::display_player_stats(player_stats, guesses);
+        }
+
+        let puzzle_name = if selection == Selection::Daily {
+            Some(puzzle_number.to_string())
+        } else {
+            puzzle_id.map(|id| id.to_string())
+        };
+        let guesses_played = &display.get_user_guess_arr()[..game.get_history().len()];
+
+        loop {
+            match display::display_end_menu(true) {
+                'P' => break,
+                'S' => match stats.get_player_stats(&username) {
+                    Some(player_stats) => display::display_player_stats(player_stats, None),
+                    None => println!("\nNo statistics yet for {}.", username),
+                },
+                'A' => display::display_analysis(
+                    &solver::analyze_game(&wordbank, game.get_history(), rules.is_hard_mode())
+                ),
+                'R' => display::display_share_menu(|style| share::build_share_text(
+                    puzzle_name.as_deref(), guesses_played, winner, rules, style
+                )),
+                _ => process::exit(0),
+            }
+        }
+
+        if let Selection::Daily | Selection::Puzzle(_) = selection {
+            selection = Selection::Random;
+        }
+
+        // The resumed round does not change the following ones.
+        if let Some((session_mode, session_rules, session_wordbank, session_word_list)) = session_settings.take() {
+            mode = session_mode;
+            rules = session_rules;
+            wordbank = session_wordbank;
+            word_list = session_word_list;
+        }
+    }
+}
+
+const HINT_COMMAND: &str = "?";
+const HINT_SUGGESTIONS: usize = 5;
+
+// Reads guesses until the game is over, redrawing the board after each one.
+// Entering the hint command shows the solver's suggestions instead. The round
+// is saved after every guess, and the save is removed once it is over.
+fn play_round(
+        game: &mut Game, 
+        display: &mut Display, 
+        wordbank: &WordBank, 
+        saved_game: &mut SavedGame
+        ) {
+    utils::clear_screen();
+    display.print_title();
+    display.print_keyboard();
+    display.print_all_guesses();
+    display::display_guesses_left(game.get_guesses_left());
+
+    while game.get_status() == GameStatus::InProgress {
+        let outcome: TurnOutcome = loop {
+            let guess: String = user_input::get_user_guess()
+                                                    .unwrap_or_else(|err| {
+                                                        eprintln!("{}", err);
+                                                        process::exit(1);
+                                                    });
+
+            if guess == HINT_COMMAND {
+                let hint = solver::get_hint(
+                    wordbank, game.get_history(), game.get_rules().is_hard_mode(),
+                    Strategy::Entropy, HINT_SUGGESTIONS
+                );
+
+                display::display_hint(&hint);
+                saved_game.record_hint(game, display);
+                continue;
+            }
+
+            match game.submit(&guess) {
+                Ok(outcome) => break outcome,
+                Err(err) => eprintln!("\n{}", err.to_str::<()>(None)),
+            }
+        };
+
+        utils::clear_screen();
+
+        display.update_key_colors_from(outcome.get_feedback());
+        display.get_user_guess(display::to_guess_color_mapping(outcome.get_feedback()));
+        display.update_user_guess_arr(game.get_history().len() - 1);
+
+        if game.get_status() == GameStatus::InProgress {
+            saved_game.update(game, display);
+        } else {
+            saved_game.remove();
+        }
+
+        display.print_title();
+        display.print_keyboard();
+        display.print_all_guesses();
+
+        if outcome.get_status() != GameStatus::Won {
+            display::display_guesses_left(game.get_guesses_left());
+        }
+    }
+}
+
+// Every board of a multi-board game needs its own answer.
+fn check_enough_answers(mode: Mode, wordbank: &WordBank) -> Result<(), AppError> {
+    match mode {
+        Mode::MultiBoard(board_count) if wordbank.get_unknown_words().len() < board_count => {
+            Err(AppError::NotEnoughAnswersErr(board_count))
+        }
+        _ => Ok(()),
+    }
+}
+
+// Plays one game on several boards at once, up to its end menu.
+fn play_multi_board_game(answers: Vec<String>, rules: Rules, wordbank: &WordBank, player_stats: Option<&PlayerStats>) {
+    let board_count = answers.len();
+    let mut game = MultiGame::new(answers, rules, wordbank);
+    let mut display = MultiDisplay::init(rules, board_count);
+
+    display.set_title(match board_count {
+        2 => "Dordle".to_string(),
+        4 => "Quordle".to_string(),
+        8 => "Octordle".to_string(),
+        _ => format!("{} boards", board_count),
+    });
+
     utils::clear_screen();
-    display.print_allowed_letters();
+    display.print_title();
+    display.print_keyboard();
     display.print_all_guesses();
-    display::display_guesses_left(guesses_left as u8);
+    display::display_guesses_left(game.get_guesses_left());
 
-    while guesses_left > 0 {
-        let guess: String = user_input::get_user_guess(&wordbank)
+    while game.get_status() == GameStatus::InProgress {
+        let outcomes = loop {
+            let guess: String = user_input::get_user_guess()
                                                     .unwrap_or_else(|err| {
                                                         eprintln!("{}", err);
                                                         process::exit(1);
                                                     });
-        let (gcm, winner_temp): (GuessColorMapping, IfWinner) = user_input::process_guess(
-            &guess, &unknown_word, &mut display);
 
-        winner = winner_temp;
+            if guess == HINT_COMMAND {
+                for (i, board) in game.get_boards().iter().enumerate() {
+                    if board.get_status() != GameStatus::InProgress {
+                        continue;
+                    }
+
+                    let hint = solver::get_hint(
+                        wordbank, board.get_history(), rules.is_hard_mode(),
+                        Strategy::Entropy, HINT_SUGGESTIONS
+                    );
+
+                    print!("Board {}:", i + 1);
+                    display::display_hint(&hint);
+                }
+                continue;
+            }
+
+            match game.submit(&guess) {
+                Ok(outcomes) => break outcomes,
+                Err(err) => eprintln!("\n{}", err.to_str::<()>(None)),
+            }
+        };
 
         utils::clear_screen();
-        
-        display.update_allowed_letters();
-        display.get_user_guess(gcm);
-        display.update_user_guess_arr(MAX_GUESSES - guesses_left);
-        guesses_left -= 1;
-        display.print_allowed_letters();
+
+        for (board, outcome) in outcomes.iter().enumerate() {
+            if let Some(outcome) = outcome {
+                let guess = display::to_guess_color_mapping(outcome.get_feedback());
+                display.update_board(board, game.get_guess_count() - 1, guess);
+            }
+        }
+
+        display.print_title();
+        display.print_keyboard();
         display.print_all_guesses();
 
-        if winner { 
-            let score = (guesses_left as u8) + 1;
-            highscore.try_insert_new_score(UserScore::new(username.clone(), score));
-            display::display_end_screen(winner, &unknown_word); 
-        } 
-        
-        display::display_guesses_left(guesses_left as u8);
+        if game.get_status() != GameStatus::Won {
+            display::display_guesses_left(game.get_guesses_left());
+        }
+    }
+
+    let winner = game.get_status() == GameStatus::Won;
+    let unsolved: Vec<&str> = game.get_boards()
+        .iter()
+        .filter(|board| board.get_status() != GameStatus::Won)
+        .map(|board| board.get_answer())
+        .collect();
+
+    display::display_end_screen(winner, &unsolved, None);
+
+    let solved_in: Vec<Option<usize>> = game.get_boards()
+        .iter()
+        .map(|board| match board.get_status() {
+            GameStatus::Won => Some(board.get_history().len()),
+            _ => None,
+        })
+        .collect();
+    let boards: Vec<&[display::GuessColorMapping]> = display.get_boards()
+        .iter()
+        .map(|board| &board[..game.get_guess_count()])
+        .collect();
+
+    loop {
+        match display::display_end_menu(false) {
+            'P' => return,
+            'S' => match player_stats {
+                Some(player_stats) => display::display_player_stats(player_stats, None),
+                None => println!("\nNo statistics yet."),
+            },
+            'R' => display::display_share_menu(|style| share::build_multi_share_text(
+                &boards, &solved_in, rules, style
+            )),
+            _ => process::exit(0),
+        }
+    }
+}
+
+// Plays reverse games until the player quits.
+fn play_reverse_games(rules: Rules, wordbank: &WordBank) -> ! {
+    loop {
+        play_reverse_game(rules, wordbank);
+
+        if display::display_play_again_menu() == 'Q' {
+            process::exit(0);
+        }
+    }
+}
+
+// Guesses the word the player has in mind, from the feedback they type in.
+fn play_reverse_game(rules: Rules, wordbank: &WordBank) {
+    let mut guesser = Guesser::new(wordbank);
+    let mut display = Display::init(rules);
+
+    display.set_title(format!(
+        "Think of a {}-letter word, and I will try to guess it.", rules.get_word_length()
+    ));
+
+    utils::clear_screen();
+    display.print_title();
+    display.print_all_guesses();
+
+    let found = loop {
+        let guesses_made = guesser.get_history().len();
+
+        if rules.get_max_guesses() == Some(guesses_made) {
+            break false;
+        }
+
+        let guess = guesser.next_guess();
+
+        println!(
+            "My guess is {}. ({} possible words left)", 
+            guess.to_uppercase(), guesser.get_candidates().len()
+        );
+
+        let feedback = loop {
+            let input = user_input::get_user_feedback(&guess).unwrap_or_else(|err| {
+                eprintln!("{}", err);
+                process::exit(1);
+            });
+
+            let feedback = user_input::parse_feedback(&guess, &input)
+                .and_then(|feedback| guesser.add_feedback(feedback.clone()).map(|_| feedback));
+
+            match feedback {
+                Ok(feedback) => break feedback,
+                Err(err) => eprintln!("\n{}", err.to_str::<()>(None)),
+            }
+        };
+
+        utils::clear_screen();
+
+        display.get_user_guess(display::to_guess_color_mapping(&feedback));
+        display.update_user_guess_arr(guesses_made);
+        display.print_title();
+        display.print_all_guesses();
+
+        if feedback.is_win() {
+            break true;
+        }
+    };
 
+    if found {
+        println!("Got it in {}!", guesser.get_history().len());
+    } else {
+        println!(
+            "I give up! It could have been one of {} words, like {}.", 
+            guesser.get_candidates().len(), guesser.get_candidates()[0].to_uppercase()
+        );
     }
-    
-    display::display_end_screen(winner, &unknown_word);
 }
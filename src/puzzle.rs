@@ -0,0 +1,88 @@
+// Puzzle IDs name an answer so that it can be played again, e.g. by a friend.
+// An ID is written as "<word length>-<index in the answer list>".
+
+use std::fmt;
+
+use crate::constants::{MIN_WORD_LENGTH, MAX_WORD_LENGTH};
+use crate::err::AppError;
+use crate::wordbank::WordBank;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct PuzzleId {
+    word_length: usize,
+    index: usize,
+}
+
+impl PuzzleId {
+    // Returns `None` if the answer is not in the word bank's answer list.
+    pub fn of(wordbank: &WordBank, answer: &str) -> Option<PuzzleId> {
+        wordbank.get_unknown_word_index(answer).map(|index| {
+            PuzzleId { word_length: wordbank.get_word_length(), index }
+        })
+    }
+
+    pub fn parse(id: &str) -> Result<PuzzleId, AppError> {
+        let invalid = || AppError::InvalidPuzzleIdErr(id.to_string());
+
+        let (word_length, index) = id.trim().split_once('-').ok_or_else(invalid)?;
+        let word_length: usize = word_length.parse().map_err(|_| invalid())?;
+        let index: usize = index.parse().map_err(|_| invalid())?;
+
+        if !(MIN_WORD_LENGTH..=MAX_WORD_LENGTH).contains(&word_length) {
+            return Err(invalid());
+        }
+
+        Ok(PuzzleId { word_length, index })
+    }
+
+    pub fn get_word_length(&self) -> usize {
+        self.word_length
+    }
+
+    pub fn get_answer(&self, wordbank: &WordBank) -> Result<String, AppError> {
+        assert_eq!(self.word_length, wordbank.get_word_length());
+
+        wordbank.get_unknown_word(self.index)
+            .cloned()
+            .ok_or_else(|| AppError::InvalidPuzzleIdErr(self.to_string()))
+    }
+}
+
+impl fmt::Display for PuzzleId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}-{}", self.word_length, self.index)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_puzzle_id_round_trip() {
+        let wordbank = WordBank::init_with_length(6);
+
+        for word in ["absent", "zonked"] {
+            let id = PuzzleId::of(&wordbank, word).unwrap();
+
+            assert_eq!(6, id.get_word_length());
+            assert_eq!(Ok(id), PuzzleId::parse(&id.to_string()));
+            assert_eq!(Ok(word.to_string()), id.get_answer(&wordbank));
+        }
+
+        assert_eq!(None, PuzzleId::of(&wordbank, "hello"));
+    }
+
+    #[test]
+    fn test_parse_invalid_puzzle_id() {
+        for id in ["", "5", "5-", "-12", "five-12", "3-12", "9-12", "5-x"] {
+            assert_eq!(Err(AppError::InvalidPuzzleIdErr(id.to_string())), PuzzleId::parse(id));
+        }
+
+        let wordbank = WordBank::init();
+        let id = PuzzleId::parse("5-99999").unwrap();
+
+        assert_eq!(Err(AppError::InvalidPuzzleIdErr("5-99999".to_string())), id.get_answer(&wordbank));
+    }
+}
@@ -0,0 +1,502 @@
+// The round in progress, saved after every accepted guess so that it can be
+// resumed if the game is closed before the round is over. Each player has at
+// most one saved round, and the save file keeps them one after the other,
+// each starting with the save header. Games running at the same time only
+// replace or remove the round of their own player.
+//
+// The answer is scrambled in the file so that it cannot be read at a glance.
+// This keeps honest players honest, it is not meant to stop anyone determined.
+
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::constants::{GUESS_LIMIT, MAX_WORD_LENGTH, MIN_WORD_LENGTH};
+use crate::display::{self, Display, FontColors, KeyColors};
+use crate::err::AppError;
+use crate::game::{Game, GameStatus, Rules};
+use crate::store;
+use crate::utils;
+use crate::wordbank::{WordBank, WordList};
+
+const SAVE_HEADER: &str = "# wordle_clone saved round";
+const SCRAMBLE_KEY: &[u8] = b"not-the-answer";
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct SavedGame {
+    username: String,
+    rules: Rules,
+    word_list: WordList,
+    // `None` for Absurdle, which has no answer until the end.
+    answer: Option<String>,
+    // The daily puzzle being played, if any.
+    puzzle_number: Option<u32>,
+    guesses: Vec<String>,
+    key_colors: KeyColors,
+    // When the round was started, in seconds since the Unix epoch. The time
+    // since then counts towards the score.
+    started_secs: u64,
+    // Rounds played with hints do not count towards the high scores.
+    used_hints: bool,
+}
+
+impl SavedGame {
+    // The round starts now.
+    pub fn new(username: String, game: &Game, word_list: WordList, puzzle_number: Option<u32>) -> SavedGame {
+        let answer = if game.is_adversarial() { None } else { Some(game.get_answer().to_string()) };
+
+        SavedGame {
+            username,
+            rules: game.get_rules(),
+            word_list,
+            answer,
+            puzzle_number,
+            guesses: Vec::new(),
+            key_colors: [None; 26],
+            started_secs: to_unix_secs(SystemTime::now()),
+            used_hints: false,
+        }
+    }
+
+    // Returns the saved rounds that can be read, the most recent first.
+    pub fn load_all() -> Vec<SavedGame> {
+        let mut rounds = match fs::read_to_string(get_save_path()) {
+            Ok(text) => SavedGame::parse_rounds(&text),
+            Err(_) => Vec::new(),
+        };
+
+        rounds.reverse();
+        rounds
+    }
+
+    // Removes this round from the save file. A newer round of the same player,
+    // e.g. from another game running at the same time, is kept.
+    pub fn remove(&self) {
+        let result = store::update_file(&get_save_path(), |current| {
+            let rounds: Vec<SavedGame> = SavedGame::parse_rounds(current)
+                .into_iter()
+                .filter(|round| round.username != self.username || round.answer != self.answer)
+                .collect();
+
+            SavedGame::rounds_to_text(&rounds)
+        });
+
+        if let Err(err) = result {
+            eprintln!("IO Error: {}", err);
+        }
+    }
+
+    pub fn get_username(&self) -> &String {
+        &self.username
+    }
+
+    pub fn get_rules(&self) -> Rules {
+        self.rules
+    }
+
+    pub fn get_word_list(&self) -> &WordList {
+        &self.word_list
+    }
+
+    pub fn is_adversarial(&self) -> bool {
+        self.answer.is_none()
+    }
+
+    pub fn get_puzzle_number(&self) -> Option<u32> {
+        self.puzzle_number
+    }
+
+    pub fn get_guesses(&self) -> &Vec<String> {
+        &self.guesses
+    }
+
+    pub fn get_started(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(self.started_secs)
+    }
+
+    pub fn has_used_hints(&self) -> bool {
+        self.used_hints
+    }
+
+    // Saved right away, so that closing the game does not forget the hint.
+    pub fn record_hint(&mut self, game: &Game, display: &Display) {
+        self.used_hints = true;
+        self.update(game, display);
+    }
+
+    // Catches up with the game and writes it to the save file.
+    pub fn update(&mut self, game: &Game, display: &Display) {
+        self.guesses = game.get_history().iter().map(|feedback| feedback.get_guess()).collect();
+        self.key_colors = *display.get_key_colors();
+
+        // The round of this player goes last, as the most recent one.
+        let result = store::update_file(&get_save_path(), |current| {
+            let mut rounds: Vec<SavedGame> = SavedGame::parse_rounds(current)
+                .into_iter()
+                .filter(|round| round.username != self.username)
+                .collect();
+
+            rounds.push(self.clone());
+            SavedGame::rounds_to_text(&rounds)
+        });
+
+        if let Err(err) = result {
+            eprintln!("IO Error: {}", err);
+        }
+    }
+
+    // Replays the saved guesses. The wordbank must be the one of the saved
+    // word list.
+    pub fn restore<'a>(&self, wordbank: &'a WordBank) -> Result<(Game<'a>, Display), AppError> {
+        let mut game = match &self.answer {
+            Some(answer) if wordbank.get_word_length() == answer.len() => {
+                Game::new(answer.clone(), self.rules, wordbank)
+            }
+            Some(_) => return Err(AppError::InvalidSavedGameErr),
+            None => Game::new_adversarial(self.rules, wordbank),
+        };
+        let mut display = Display::init(self.rules);
+
+        for (i, guess) in self.guesses.iter().enumerate() {
+            let outcome = game.submit(guess).map_err(|_| AppError::InvalidSavedGameErr)?;
+
+            display.get_user_guess(display::to_guess_color_mapping(outcome.get_feedback()));
+            display.update_user_guess_arr(i);
+        }
+
+        // A finished round has nothing left to resume.
+        if game.get_status() != GameStatus::InProgress {
+            return Err(AppError::InvalidSavedGameErr);
+        }
+
+        display.set_key_colors(self.key_colors);
+
+        Ok((game, display))
+    }
+
+    // Rounds that cannot be read are left out.
+    fn parse_rounds(text: &str) -> Vec<SavedGame> {
+        let mut round_texts: Vec<String> = Vec::new();
+
+        for line in text.lines() {
+            if round_texts.is_empty() || line.trim() == SAVE_HEADER {
+                round_texts.push(String::new());
+            }
+
+            if let Some(round_text) = round_texts.last_mut() {
+                round_text.push_str(line);
+                round_text.push('\n');
+            }
+        }
+
+        round_texts.iter().filter_map(|round_text| SavedGame::from_text(round_text)).collect()
+    }
+
+    fn rounds_to_text(rounds: &[SavedGame]) -> String {
+        rounds.iter().map(|round| round.to_text()).collect()
+    }
+
+    fn to_text(&self) -> String {
+        let mut lines: Vec<String> = vec![SAVE_HEADER.to_string()];
+
+        lines.push(format!("username = {}", self.username));
+        lines.push(format!("length = {}", self.rules.get_word_length()));
+        lines.push(format!("guesses = {}", match self.rules.get_max_guesses() {
+            Some(max_guesses) => max_guesses.to_string(),
+            None => "unlimited".to_string(),
+        }));
+        lines.push(format!("hard = {}", self.rules.is_hard_mode()));
+
+        match &self.word_list {
+            WordList::Common => lines.push("list = common".to_string()),
+            WordList::Full => lines.push("list = full".to_string()),
+            WordList::Pack(name) => lines.push(format!("list = pack {}", name)),
+            WordList::Files { answers, allowed } => {
+                lines.push("list = files".to_string());
+
+                if let Some(answers) = answers {
+                    lines.push(format!("answers = {}", answers.display()));
+                }
+
+                if let Some(allowed) = allowed {
+                    lines.push(format!("allowed = {}", allowed.display()));
+                }
+            }
+        }
+
+        if let Some(answer) = &self.answer {
+            lines.push(format!("answer = {}", scramble(answer, &self.username)));
+        }
+
+        if let Some(puzzle_number) = self.puzzle_number {
+            lines.push(format!("daily = {}", puzzle_number));
+        }
+
+        lines.push(format!("keys = {}", key_colors_to_string(&self.key_colors)));
+        lines.push(format!("started = {}", self.started_secs));
+        lines.push(format!("hints = {}", self.used_hints));
+
+        for guess in &self.guesses {
+            lines.push(format!("guess = {}", guess));
+        }
+
+        lines.join("\n") + "\n"
+    }
+
+    fn from_text(text: &str) -> Option<SavedGame> {
+        let mut fields: Vec<(&str, &str)> = Vec::new();
+
+        for line in text.lines().map(|line| line.trim()) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (key, value) = line.split_once('=')?;
+            fields.push((key.trim(), value.trim()));
+        }
+
+        let get_field = |key: &str| fields.iter().find(|(k, _)| *k == key).map(|(_, v)| *v);
+
+        let username = get_field("username").filter(|name| !name.is_empty())?.to_string();
+
+        let word_length: usize = get_field("length")?.parse().ok()?;
+        let max_guesses = match get_field("guesses")? {
+            "unlimited" => None,
+            max_guesses => Some(max_guesses.parse::<usize>().ok().filter(|n| (1..=GUESS_LIMIT).contains(n))?),
+        };
+        let hard_mode: bool = get_field("hard")?.parse().ok()?;
+
+        if !(MIN_WORD_LENGTH..=MAX_WORD_LENGTH).contains(&word_length) {
+            return None;
+        }
+
+        let word_list = match get_field("list")?.split_once(' ') {
+            Some(("pack", name)) => WordList::Pack(name.to_string()),
+            _ => match get_field("list")? {
+                "common" => WordList::Common,
+                "full" => WordList::Full,
+                "files" => WordList::Files {
+                    answers: get_field("answers").map(PathBuf::from),
+                    allowed: get_field("allowed").map(PathBuf::from),
+                },
+                _ => return None,
+            },
+        };
+
+        let answer = match get_field("answer") {
+            Some(scrambled) => Some(unscramble(scrambled, &username)?),
+            None => None,
+        };
+        let puzzle_number = match get_field("daily") {
+            Some(puzzle_number) => Some(puzzle_number.parse().ok()?),
+            None => None,
+        };
+
+        // Rounds saved before the start time was kept start from now.
+        let started_secs = match get_field("started") {
+            Some(started_secs) => started_secs.parse().ok()?,
+            None => to_unix_secs(SystemTime::now()),
+        };
+
+        // Rounds saved before hints were kept did not record them.
+        let used_hints: bool = match get_field("hints") {
+            Some(used_hints) => used_hints.parse().ok()?,
+            None => false,
+        };
+
+        let guesses = fields.iter()
+            .filter(|(key, _)| *key == "guess")
+            .map(|(_, guess)| guess.to_string())
+            .collect();
+
+        Some(SavedGame {
+            username,
+            rules: Rules::new(word_length, max_guesses, hard_mode),
+            word_list,
+            answer,
+            puzzle_number,
+            guesses,
+            key_colors: key_colors_from_str(get_field("keys")?)?,
+            started_secs,
+            used_hints,
+        })
+    }
+}
+
+fn to_unix_secs(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH).map(|duration| duration.as_secs()).unwrap_or_default()
+}
+
+fn get_save_path() -> PathBuf {
+    let mut save_path = utils::get_data_dir();
+
+    save_path.push("saved_game.txt");
+    save_path
+}
+
+// XORs the word with a key mixed with the username, then writes it in hex.
+fn scramble(word: &str, username: &str) -> String {
+    word.bytes()
+        .zip(get_scramble_key(username))
+        .map(|(byte, key)| format!("{:02x}", byte ^ key))
+        .collect()
+}
+
+fn unscramble(scrambled: &str, username: &str) -> Option<String> {
+    if !scrambled.len().is_multiple_of(2) || !scrambled.is_ascii() {
+        return None;
+    }
+
+    let bytes = (0..scrambled.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&scrambled[i..i + 2], 16).ok())
+        .collect::<Option<Vec<u8>>>()?;
+
+    let word: String = bytes.iter()
+        .zip(get_scramble_key(username))
+        .map(|(byte, key)| (byte ^ key) as char)
+        .collect();
+
+    if word.is_empty() || !word.chars().all(|c| c.is_ascii_lowercase()) {
+        return None;
+    }
+
+    Some(word)
+}
+
+fn get_scramble_key(username: &str) -> impl Iterator<Item = u8> + '_ {
+    SCRAMBLE_KEY.iter()
+        .cycle()
+        .zip(username.bytes().cycle())
+        .enumerate()
+        .map(|(i, (key, name))| key ^ name ^ (i as u8).wrapping_mul(31))
+}
+
+// One character per letter: g for green, b for blue, x for gray and . for
+// letters that were not guessed yet.
+fn key_colors_to_string(key_colors: &KeyColors) -> String {
+    key_colors.iter()
+        .map(|color| match color {
+            Some(FontColors::Green) => 'g',
+            Some(FontColors::Blue) => 'b',
+            Some(FontColors::Gray) => 'x',
+            None => '.',
+        })
+        .collect()
+}
+
+fn key_colors_from_str(text: &str) -> Option<KeyColors> {
+    let mut key_colors: KeyColors = [None; 26];
+
+    if text.chars().count() != key_colors.len() {
+        return None;
+    }
+
+    for (key_color, c) in key_colors.iter_mut().zip(text.chars()) {
+        *key_color = match c {
+            'g' => Some(FontColors::Green),
+            'b' => Some(FontColors::Blue),
+            'x' => Some(FontColors::Gray),
+            '.' => None,
+            _ => return None,
+        };
+    }
+
+    Some(key_colors)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn play(wordbank: &WordBank, answer: &str, guesses: &[&str]) -> (SavedGame, Display) {
+        let rules = Rules::new(5, Some(6), true);
+        let mut game = Game::new(answer.to_string(), rules, wordbank);
+        let mut display = Display::init(rules);
+
+        for guess in guesses {
+            let outcome = game.submit(guess).unwrap();
+            display.update_key_colors_from(outcome.get_feedback());
+        }
+
+        let mut saved_game = SavedGame::new("abcde".to_string(), &game, WordList::Common, Some(42));
+        saved_game.guesses = game.get_history().iter().map(|feedback| feedback.get_guess()).collect();
+        saved_game.key_colors = *display.get_key_colors();
+        saved_game.started_secs = 1_700_000_000;
+
+        (saved_game, display)
+    }
+
+    #[test]
+    fn test_saved_game_text() {
+        let wordbank = WordBank::init();
+        let (saved_game, _) = play(&wordbank, "hello", &["crane", "shell"]);
+
+        let text = saved_game.to_text();
+
+        assert!(!text.contains("hello"));
+        assert!(text.contains("started = 1700000000\nhints = false\nguess = crane\nguess = shell\n"));
+        assert_eq!(Some(saved_game), SavedGame::from_text(&text));
+
+        let files = SavedGame {
+            word_list: WordList::Files { answers: Some(PathBuf::from("a.txt")), allowed: None },
+            answer: None,
+            puzzle_number: None,
+            ..play(&wordbank, "hello", &[]).0
+        };
+
+        assert_eq!(Some(files.clone()), SavedGame::from_text(&files.to_text()));
+
+        let hinted = SavedGame { used_hints: true, ..play(&wordbank, "hello", &["crane"]).0 };
+
+        assert_eq!(Some(hinted.clone()), SavedGame::from_text(&hinted.to_text()));
+        assert!(!SavedGame::from_text(&hinted.to_text().replace("hints = true\n", "")).unwrap().has_used_hints());
+        assert_eq!(None, SavedGame::from_text("username = abcde\nlength = 5\n"));
+        assert_eq!(None, SavedGame::from_text(&text.replace("keys = ", "keys = z")));
+    }
+
+    #[test]
+    fn test_parse_rounds() {
+        let wordbank = WordBank::init();
+        let (first, _) = play(&wordbank, "hello", &["crane"]);
+        let second = SavedGame { username: "vwxyz".to_string(), ..play(&wordbank, "world", &["shell"]).0 };
+
+        let text = SavedGame::rounds_to_text(&[first.clone(), second.clone()]);
+
+        assert_eq!(vec![first.clone(), second], SavedGame::parse_rounds(&text));
+        assert_eq!(vec![first.clone()], SavedGame::parse_rounds(&first.to_text()));
+        assert_eq!(
+            vec![first.clone()],
+            SavedGame::parse_rounds(&format!("{}{}\nlength = 5\n", first.to_text(), SAVE_HEADER))
+        );
+        assert!(SavedGame::parse_rounds("").is_empty());
+    }
+
+    #[test]
+    fn test_restore() {
+        let wordbank = WordBank::init();
+        let (saved_game, display) = play(&wordbank, "hello", &["crane", "shell"]);
+
+        let (game, restored_display) = saved_game.restore(&wordbank).unwrap();
+
+        assert_eq!("hello", game.get_answer());
+        assert_eq!(2, game.get_history().len());
+        assert_eq!(display.get_key_colors(), restored_display.get_key_colors());
+        assert_eq!('S', restored_display.get_user_guess_arr()[1][0].0.to_ascii_uppercase());
+
+        let (finished, _) = play(&wordbank, "hello", &["crane", "hello"]);
+
+        assert_eq!(Some(AppError::InvalidSavedGameErr), finished.restore(&wordbank).err());
+    }
+
+    #[test]
+    fn test_scramble() {
+        let scrambled = scramble("hello", "abcde");
+
+        assert_ne!("hello", scrambled);
+        assert_eq!(Some("hello".to_string()), unscramble(&scrambled, "abcde"));
+        assert_ne!(Some("hello".to_string()), unscramble(&scrambled, "vwxyz"));
+        assert_eq!(None, unscramble("zz", "abcde"));
+    }
+}
@@ -0,0 +1,237 @@
+// How a won round is turned into points for the high scores:
+//
+//   guesses     100 points for every guess fewer than 7, e.g. 400 for a win
+//               in 3 guesses, whatever the guess budget is
+//   time        300 points, minus 1 for every second the round took
+//   difficulty  up to 200 points, for answers with more unusual letters than
+//               the other answers of the list
+//   hard mode   20% more of all of the above
+//
+// Old high scores, which only counted the guesses left, are worth their
+// guess points only, on the guess budget they were written with.
+
+use std::time::{Duration, SystemTime};
+
+use crate::constants::MAX_GUESSES;
+
+const POINTS_PER_GUESS: u32 = 100;
+const TIME_POINTS: u32 = 300;
+const DIFFICULTY_POINTS: u32 = 200;
+const HARD_MODE_BONUS_PERCENT: u32 = 20;
+
+// The guess budget of the versions that wrote high scores without a header.
+pub const UNVERSIONED_MAX_GUESSES: usize = 5;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub struct ScoreBreakdown {
+    guess_points: u32,
+    time_points: u32,
+    difficulty_points: u32,
+    hard_mode_points: u32,
+}
+
+impl ScoreBreakdown {
+    pub fn new(
+        guess_points: u32,
+        time_points: u32,
+        difficulty_points: u32,
+        hard_mode_points: u32,
+    ) -> ScoreBreakdown {
+        ScoreBreakdown { guess_points, time_points, difficulty_points, hard_mode_points }
+    }
+
+    pub fn get_guess_points(&self) -> u32 {
+        self.guess_points
+    }
+
+    pub fn get_time_points(&self) -> u32 {
+        self.time_points
+    }
+
+    pub fn get_difficulty_points(&self) -> u32 {
+        self.difficulty_points
+    }
+
+    pub fn get_hard_mode_points(&self) -> u32 {
+        self.hard_mode_points
+    }
+
+    pub fn get_total(&self) -> u32 {
+        self.guess_points + self.time_points + self.difficulty_points + self.hard_mode_points
+    }
+
+    // Whether the points are within what a round can earn.
+    pub fn is_possible(&self) -> bool {
+        let base_points = self.guess_points + self.time_points + self.difficulty_points;
+
+        self.guess_points <= POINTS_PER_GUESS * MAX_GUESSES as u32
+            && self.time_points <= TIME_POINTS
+            && self.difficulty_points <= DIFFICULTY_POINTS
+            && self.hard_mode_points <= base_points * HARD_MODE_BONUS_PERCENT / 100
+    }
+}
+
+// `difficulty` goes from 0 to 1, see `get_answer_difficulty`.
+pub fn score_round(guesses: usize, elapsed: Duration, hard_mode: bool, difficulty: f64) -> ScoreBreakdown {
+    assert_ne!(0, guesses);
+
+    let guess_points = POINTS_PER_GUESS * (MAX_GUESSES + 1).saturating_sub(guesses) as u32;
+    let time_points = TIME_POINTS.saturating_sub(elapsed.as_secs().min(TIME_POINTS as u64) as u32);
+    let difficulty_points = (DIFFICULTY_POINTS as f64 * difficulty.clamp(0.0, 1.0)).round() as u32;
+
+    let hard_mode_points = if hard_mode {
+        (guess_points + time_points + difficulty_points) * HARD_MODE_BONUS_PERCENT / 100
+    } else { 0 };
+
+    ScoreBreakdown { guess_points, time_points, difficulty_points, hard_mode_points }
+}
+
+// Scores of the high score files without a header, which were the number of
+// guesses left plus one out of 5 guesses, e.g. 5 for a win in 1 guess.
+pub fn from_unversioned_score(score: u8) -> ScoreBreakdown {
+    let guesses = (UNVERSIONED_MAX_GUESSES + 1).saturating_sub(score as usize).max(1);
+
+    score_round(guesses, Duration::from_secs(TIME_POINTS as u64), false, 0.0)
+}
+
+// Scores of version 1 high score files, which were the number of guesses left
+// plus one out of the guess budget of the game. The budget was not kept, so
+// each is worth 100 points, as with the default budget, up to the guess points
+// of a win in 1 guess, e.g. 20 out of 20 guesses is worth 600 points.
+pub fn from_version_1_score(score: u8) -> ScoreBreakdown {
+    let guess_points = (POINTS_PER_GUESS * score as u32).min(POINTS_PER_GUESS * MAX_GUESSES as u32);
+
+    ScoreBreakdown::new(guess_points, 0, 0, 0)
+}
+
+// The share of the other answers whose letters are more common than the ones
+// of this answer, from 0 for the most ordinary answer to 1 for the most
+// unusual one. A letter is as common as the number of answers it appears in,
+// and repeated letters only count once, which makes them harder too.
+pub fn get_answer_difficulty(answer: &str, answers: &[String]) -> f64 {
+    let mut letter_counts = [0_u32; 26];
+
+    for word in answers {
+        for letter in get_distinct_letters(word) {
+            letter_counts[letter] += 1;
+        }
+    }
+
+    let commonness = |word: &str| -> u32 {
+        get_distinct_letters(word).iter().map(|&letter| letter_counts[letter]).sum()
+    };
+
+    let answer_commonness = commonness(answer);
+    let more_common = answers.iter().filter(|word| commonness(word) > answer_commonness).count();
+
+    more_common as f64 / answers.len().saturating_sub(1).max(1) as f64
+}
+
+fn get_distinct_letters(word: &str) -> Vec<usize> {
+    let mut letters: Vec<usize> = word.bytes()
+        .filter(|byte| byte.is_ascii_lowercase())
+        .map(|byte| (byte - b'a') as usize)
+        .collect();
+
+    letters.sort();
+    letters.dedup();
+    letters
+}
+
+// For the help screen.
+pub fn describe_formula() -> String {
+    format!(
+        "Scoring: a win is worth {} points for every guess fewer than {}, plus {} points \n\
+        minus 1 for every second it took, plus up to {} points for an answer with unusual \n\
+        letters. Hard mode adds {}% to all of it.",
+        POINTS_PER_GUESS, MAX_GUESSES + 1, TIME_POINTS, DIFFICULTY_POINTS, HARD_MODE_BONUS_PERCENT
+    )
+}
+
+// Measures how long a round takes from the time it was started, which is kept
+// with the saved round. A resumed round counts all the time since then,
+// including the time the game was closed.
+#[derive(Copy, Clone, Debug)]
+pub struct Stopwatch {
+    started: SystemTime,
+}
+
+impl Stopwatch {
+    pub fn start_at(started: SystemTime) -> Stopwatch {
+        Stopwatch { started }
+    }
+
+    pub fn get_started(&self) -> SystemTime {
+        self.started
+    }
+
+    // A clock set back counts as no time at all.
+    pub fn get_elapsed(&self) -> Duration {
+        SystemTime::now().duration_since(self.started).unwrap_or_default()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_score_round() {
+        let score = score_round(3, Duration::from_secs(100), false, 0.5);
+
+        assert_eq!(ScoreBreakdown::new(400, 200, 100, 0), score);
+        assert_eq!(700, score.get_total());
+
+        let score = score_round(1, Duration::from_millis(500), true, 1.0);
+
+        assert_eq!(ScoreBreakdown::new(600, 300, 200, 220), score);
+        assert!(score.is_possible());
+
+        assert_eq!(
+            ScoreBreakdown::new(0, 0, 0, 0),
+            score_round(12, Duration::from_secs(1000), true, 0.0)
+        );
+    }
+
+    #[test]
+    fn test_ties_are_rare() {
+        let fast = score_round(4, Duration::from_secs(40), false, 0.3);
+        let slow = score_round(4, Duration::from_secs(41), false, 0.3);
+
+        assert!(fast.get_total() > slow.get_total());
+    }
+
+    #[test]
+    fn test_from_unversioned_score() {
+        assert_eq!(ScoreBreakdown::new(600, 0, 0, 0), from_unversioned_score(5));
+        assert_eq!(ScoreBreakdown::new(200, 0, 0, 0), from_unversioned_score(1));
+    }
+
+    #[test]
+    fn test_from_version_1_score() {
+        assert_eq!(ScoreBreakdown::new(600, 0, 0, 0), from_version_1_score(6));
+        assert_eq!(ScoreBreakdown::new(100, 0, 0, 0), from_version_1_score(1));
+        assert_eq!(ScoreBreakdown::new(600, 0, 0, 0), from_version_1_score(20));
+    }
+
+    #[test]
+    fn test_is_possible() {
+        assert!(ScoreBreakdown::new(600, 300, 200, 220).is_possible());
+        assert!(!ScoreBreakdown::new(700, 0, 0, 0).is_possible());
+        assert!(!ScoreBreakdown::new(100, 0, 0, 100).is_possible());
+    }
+
+    #[test]
+    fn test_get_answer_difficulty() {
+        let answers: Vec<String> = ["arose", "stare", "raise", "fuzzy", "quirk"]
+            .iter()
+            .map(|word| word.to_string())
+            .collect();
+
+        assert_eq!(0.0, get_answer_difficulty("raise", &answers));
+        assert_eq!(0.25, get_answer_difficulty("stare", &answers));
+        assert_eq!(0.75, get_answer_difficulty("quirk", &answers));
+        assert_eq!(1.0, get_answer_difficulty("fuzzy", &answers));
+    }
+}
@@ -0,0 +1,268 @@
+// Scores a guess against the answer. Nothing in here knows about the terminal,
+// so it can be used by the game, a solver, or tests alike.
+
+use std::collections::HashMap;
+
+use crate::constants::MAX_WORD_LENGTH;
+
+// How a single letter of a guess relates to the answer.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum LetterScore {
+    Correct,
+    Present,
+    Absent,
+}
+
+pub type LetterFeedback = (char, LetterScore);
+
+// A feedback packed into a number, reading the letters as base-3 digits
+// (ABSENT = 0, PRESENT = 1, CORRECT = 2) with the first letter as the lowest
+// digit. Used where many words have to be scored quickly.
+pub type Pattern = u16;
+
+impl LetterScore {
+    fn to_digit(self) -> Pattern {
+        match self {
+            LetterScore::Absent  => 0,
+            LetterScore::Present => 1,
+            LetterScore::Correct => 2,
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Feedback {
+    letters: Vec<LetterFeedback>,
+}
+
+impl Feedback {
+    // Builds a feedback that was not scored here, e.g. one typed in by the player.
+    pub fn new(letters: Vec<LetterFeedback>) -> Feedback {
+        Feedback { letters }
+    }
+
+    pub fn get_letters(&self) -> &Vec<LetterFeedback> {
+        &self.letters
+    }
+
+    pub fn get_scores(&self) -> Vec<LetterScore> {
+        self.letters.iter().map(|&(_, s)| s).collect()
+    }
+
+    pub fn is_win(&self) -> bool {
+        self.letters.iter().all(|&(_, s)| s == LetterScore::Correct)
+    }
+
+    pub fn get_guess(&self) -> String {
+        self.letters.iter().map(|&(l, _)| l).collect()
+    }
+
+    pub fn get_pattern(&self) -> Pattern {
+        self.letters
+            .iter()
+            .rev()
+            .fold(0, |pattern, &(_, s)| pattern * 3 + s.to_digit())
+    }
+}
+
+// Maps each letter of the guess to how correct it is with respect to the
+// letters of the answer. Both words must be lower-cased and of equal length.
+pub fn score(guess: &str, answer: &str) -> Feedback {
+    assert_eq!(guess.to_lowercase().as_str(), guess);
+    assert_eq!(answer.to_lowercase().as_str(), answer);
+
+    let guess_arr: Vec<char> = guess.chars().collect();
+    let answer_arr: Vec<char> = answer.chars().collect();
+
+    assert_eq!(guess_arr.len(), answer_arr.len());
+
+    let mut letter_occurrences: HashMap<char, u8> = HashMap::new();
+
+    for letter in answer.chars() {
+        *letter_occurrences.entry(letter).or_insert(0) += 1;
+    }
+
+    let mut scores: Vec<Option<LetterScore>> = vec![None; guess_arr.len()];
+
+    score_correct_letters(&guess_arr, &answer_arr, &mut letter_occurrences, &mut scores);
+    score_other_letters(&guess_arr, &answer_arr, &mut letter_occurrences, &mut scores);
+
+    let letters = guess_arr
+        .into_iter()
+        .zip(scores)
+        .map(|(letter, s)| (letter, s.expect("Every letter should have been scored.")))
+        .collect();
+
+    Feedback { letters }
+}
+
+// Same as `score`, but returns the packed pattern and works on the bytes of
+// lower-cased ASCII words without allocating.
+pub fn score_pattern(guess: &[u8], answer: &[u8]) -> Pattern {
+    debug_assert_eq!(guess.len(), answer.len());
+    debug_assert!(guess.len() <= MAX_WORD_LENGTH);
+
+    let mut unclaimed = [0u8; 26];
+    let mut digits = [0 as Pattern; MAX_WORD_LENGTH];
+
+    for i in 0..guess.len() {
+        if guess[i] == answer[i] {
+            digits[i] = 2;
+        } else {
+            unclaimed[(answer[i] - b'a') as usize] += 1;
+        }
+    }
+
+    for i in 0..guess.len() {
+        let letter = (guess[i] - b'a') as usize;
+
+        if digits[i] != 2 && unclaimed[letter] > 0 {
+            unclaimed[letter] -= 1;
+            digits[i] = 1;
+        }
+    }
+
+    digits[..guess.len()]
+        .iter()
+        .rev()
+        .fold(0, |pattern, &digit| pattern * 3 + digit)
+}
+
+// Scores the guess against every one of `answers` at once.
+pub fn score_patterns(guess: &str, answers: &[String]) -> Vec<Pattern> {
+    answers.iter()
+           .map(|answer| score_pattern(guess.as_bytes(), answer.as_bytes()))
+           .collect()
+}
+
+// If the i-th letter in the guess matches the i-th letter of the answer, this
+// marks the letter as CORRECT.
+fn score_correct_letters(
+    guess_arr: &[char],
+    answer_arr: &[char],
+    letter_occurrences: &mut HashMap<char, u8>,
+    scores: &mut [Option<LetterScore>],
+) {
+    for i in 0..guess_arr.len() {
+        if guess_arr[i] == answer_arr[i] {
+            *letter_occurrences.entry(guess_arr[i]).or_insert(0) -= 1;
+            scores[i] = Some(LetterScore::Correct);
+        }
+    }
+}
+
+// If the i-th letter in the guess matches a j-th letter in the answer, where
+// i != j, and that occurrence has not been claimed yet, this marks the letter
+// as PRESENT. Otherwise the letter is ABSENT.
+fn score_other_letters(
+    guess_arr: &[char],
+    answer_arr: &[char],
+    letter_occurrences: &mut HashMap<char, u8>,
+    scores: &mut [Option<LetterScore>],
+) {
+    for i in 0..guess_arr.len() {
+        let letter = guess_arr[i];
+
+        if letter == answer_arr[i] {
+            continue;
+        }
+
+        match letter_occurrences.get_mut(&letter) {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                scores[i] = Some(LetterScore::Present);
+            }
+            _ => scores[i] = Some(LetterScore::Absent),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use LetterScore::*;
+
+    #[test]
+    fn test_score1() {
+        let words: [&str; 5] = [
+            "hello",
+            "catch",
+            "swear",
+            "slick",
+            "zebra"
+        ];
+
+        for word in words {
+            let feedback = score(word, word);
+
+            assert_eq!(
+                (vec![Correct; 5], true),
+                (feedback.get_scores(), feedback.is_win())
+            );
+        }
+    }
+
+    #[test]
+    fn test_score2() {
+        let feedback = score("tenet", "catch");
+
+        assert_eq!(
+            (vec![Present, Absent, Absent, Absent, Absent], false),
+            (feedback.get_scores(), feedback.is_win())
+        );
+    }
+
+    #[test]
+    fn test_score3() {
+        let feedback = score("shell", "hello");
+
+        assert_eq!(
+            (vec![Absent, Present, Present, Correct, Present], false),
+            (feedback.get_scores(), feedback.is_win())
+        );
+    }
+
+    #[test]
+    fn test_score_keeps_guess_letters() {
+        let feedback = score("shell", "hello");
+
+        assert_eq!("shell", feedback.get_guess());
+    }
+
+    #[test]
+    fn test_score_pattern() {
+        let pairs: [(&str, &str); 6] = [
+            ("hello", "hello"),
+            ("tenet", "catch"),
+            ("shell", "hello"),
+            ("llama", "hello"),
+            ("speed", "abide"),
+            ("eerie", "there"),
+        ];
+
+        for (guess, answer) in pairs {
+            assert_eq!(
+                score(guess, answer).get_pattern(),
+                score_pattern(guess.as_bytes(), answer.as_bytes())
+            );
+        }
+
+        // ABSENT, PRESENT, PRESENT, CORRECT, PRESENT
+        assert_eq!(3 + 9 + 2 * 27 + 81, score("shell", "hello").get_pattern());
+    }
+
+    #[test]
+    fn test_score_patterns() {
+        let answers: Vec<String> = ["hello", "catch", "shell"].iter().map(|a| a.to_string()).collect();
+
+        assert_eq!(
+            vec![
+                score("shell", "hello").get_pattern(),
+                score("shell", "catch").get_pattern(),
+                score("shell", "shell").get_pattern(),
+            ],
+            score_patterns("shell", &answers)
+        );
+    }
+}
@@ -0,0 +1,225 @@
+// The shareable result of a game: a header and one row of squares per guess,
+// without revealing any letter.
+
+use std::env;
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::path::Path;
+
+use crate::display::{FontColors, GuessColorMapping};
+use crate::game::Rules;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ShareStyle {
+    Emoji,
+    Ascii,
+}
+
+impl ShareStyle {
+    // Falls back to ASCII unless the locale says the terminal speaks UTF-8.
+    pub fn detect() -> ShareStyle {
+        let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+            .iter()
+            .filter_map(|var| env::var(var).ok())
+            .find(|value| !value.is_empty())
+            .unwrap_or_default()
+            .to_lowercase();
+
+        if locale.contains("utf-8") || locale.contains("utf8") || env::var("WT_SESSION").is_ok() {
+            ShareStyle::Emoji
+        } else {
+            ShareStyle::Ascii
+        }
+    }
+
+    fn square(&self, color: FontColors) -> &'static str {
+        match (self, color) {
+            (ShareStyle::Emoji, FontColors::Green) => "🟩",
+            (ShareStyle::Emoji, FontColors::Blue)  => "🟨",
+            (ShareStyle::Emoji, FontColors::Gray)  => "⬛",
+            (ShareStyle::Ascii, FontColors::Green) => "G",
+            (ShareStyle::Ascii, FontColors::Blue)  => "Y",
+            (ShareStyle::Ascii, FontColors::Gray)  => ".",
+        }
+    }
+}
+
+// `puzzle_name` is the daily puzzle number or the puzzle ID, and `guesses` are
+// the rows of the board that were played. A lost game is shown as "X/6" like in
+// the original, and hard mode games are marked with a "*".
+pub fn build_share_text(
+    puzzle_name: Option<&str>,
+    guesses: &[GuessColorMapping],
+    winner: bool,
+    rules: Rules,
+    style: ShareStyle,
+) -> String {
+    let mut share_text = String::from("Wordle-clone");
+
+    if let Some(puzzle_name) = puzzle_name {
+        share_text.push_str(&format!(" #{}", puzzle_name));
+    }
+
+    let guess_count = if winner { guesses.len().to_string() } else { "X".to_string() };
+
+    share_text.push_str(&format!(" {}", guess_count));
+
+    if let Some(max_guesses) = rules.get_max_guesses() {
+        share_text.push_str(&format!("/{}", max_guesses));
+    }
+
+    if rules.is_hard_mode() {
+        share_text.push('*');
+    }
+
+    share_text.push('\n');
+
+    for guess in guesses {
+        share_text.push('\n');
+
+        for &(_, color) in guess {
+            share_text.push_str(style.square(color));
+        }
+    }
+
+    share_text
+}
+
+// Like `build_share_text`, with the boards of a multi-board game side by side.
+// `solved_in` tells how many guesses each board took, or `None` if it was not
+// solved, and each board has a row for every guess made in the game.
+pub fn build_multi_share_text(
+    boards: &[&[GuessColorMapping]],
+    solved_in: &[Option<usize>],
+    rules: Rules,
+    style: ShareStyle,
+) -> String {
+    const EMPTY_SQUARE: &str = "  ";
+
+    let mut share_text = format!("Wordle-clone x{}", boards.len());
+
+    let counts: Vec<String> = solved_in
+        .iter()
+        .map(|guesses| guesses.map_or("X".to_string(), |guesses| guesses.to_string()))
+        .collect();
+
+    share_text.push_str(&format!(" {}", counts.join(" ")));
+
+    if let Some(max_guesses) = rules.get_max_guesses() {
+        share_text.push_str(&format!("/{}", max_guesses));
+    }
+
+    if rules.is_hard_mode() {
+        share_text.push('*');
+    }
+
+    share_text.push('\n');
+
+    let rows = boards.iter().map(|board| board.len()).max().unwrap_or(0);
+
+    for row in 0..rows {
+        share_text.push('\n');
+
+        let line: Vec<String> = boards
+            .iter()
+            .zip(solved_in)
+            .map(|(board, &solved_in)| {
+                let word_length = board.first().map_or(0, |guess| guess.len());
+
+                // Nothing is left to show once a board is solved.
+                if solved_in.is_some_and(|guesses| row >= guesses) {
+                    return EMPTY_SQUARE.repeat(word_length);
+                }
+
+                board[row].iter().map(|&(_, color)| style.square(color)).collect()
+            })
+            .collect();
+
+        share_text.push_str(line.join(" ").trim_end());
+    }
+
+    share_text
+}
+
+// An existing file is left alone rather than overwritten.
+pub fn write_share_text(path: &Path, share_text: &str) -> io::Result<()> {
+    let mut file = OpenOptions::new().write(true).create_new(true).open(path)?;
+
+    file.write_all(format!("{}\n", share_text).as_bytes())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::display;
+    use crate::scoring;
+    use std::{fs, process};
+
+    fn get_rows(guesses: &[&str], answer: &str) -> Vec<GuessColorMapping> {
+        guesses.iter()
+               .map(|guess| display::to_guess_color_mapping(&scoring::score(guess, answer)))
+               .collect()
+    }
+
+    #[test]
+    fn test_build_share_text_emoji() {
+        let rows = get_rows(&["shell", "hello"], "hello");
+        let share_text = build_share_text(
+            Some("12"), &rows, true, Rules::default(), ShareStyle::Emoji
+        );
+
+        assert_eq!("Wordle-clone #12 2/6\n\n⬛🟨🟨🟩🟨\n🟩🟩🟩🟩🟩", share_text);
+    }
+
+    #[test]
+    fn test_build_share_text_ascii() {
+        let rows = get_rows(&["tenet"], "catch");
+        let share_text = build_share_text(
+            Some("5-70"), &rows, false, Rules::new(5, Some(1), true), ShareStyle::Ascii
+        );
+
+        assert_eq!("Wordle-clone #5-70 X/1*\n\nY....", share_text);
+        assert!(!share_text.to_lowercase().contains("tenet"));
+    }
+
+    #[test]
+    fn test_build_multi_share_text() {
+        let first = get_rows(&["catch", "hello"], "hello");
+        let second = get_rows(&["catch", "hello"], "catch");
+        let share_text = build_multi_share_text(
+            &[&first, &second], &[Some(2), Some(1)], Rules::new(5, Some(7), false), ShareStyle::Ascii
+        );
+
+        assert_eq!("Wordle-clone x2 2 1/7\n\n....Y GGGGG\nGGGGG", share_text);
+
+        let share_text = build_multi_share_text(
+            &[&first[..1], &second[..1]], &[None, Some(1)], Rules::new(5, Some(1), true), ShareStyle::Ascii
+        );
+
+        assert_eq!("Wordle-clone x2 X 1/1*\n\n....Y GGGGG", share_text);
+    }
+
+    #[test]
+    fn test_build_share_text_unlimited() {
+        let rows = get_rows(&["hello"], "hello");
+        let share_text = build_share_text(
+            None, &rows, true, Rules::new(5, None, false), ShareStyle::Ascii
+        );
+
+        assert_eq!("Wordle-clone 1\n\nGGGGG", share_text);
+    }
+
+    #[test]
+    fn test_write_share_text_keeps_existing_file() {
+        let path = env::temp_dir().join(format!("wordle_clone_share_{}.txt", process::id()));
+
+        write_share_text(&path, "first").unwrap();
+        let err = write_share_text(&path, "second").unwrap_err();
+
+        assert_eq!(io::ErrorKind::AlreadyExists, err.kind());
+        assert_eq!("first\n", fs::read_to_string(&path).unwrap());
+
+        fs::remove_file(path).unwrap();
+    }
+}
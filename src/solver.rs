@@ -0,0 +1,412 @@
+// Suggests guesses. Given the feedback so far, the solver narrows the answer
+// list down to the words that are still possible, and ranks every allowed
+// guess by how well it splits those words apart.
+
+use std::cmp::Ordering;
+
+use crate::scoring::{self, Feedback, Pattern};
+use crate::err::AppError;
+use crate::user_input;
+use crate::wordbank::WordBank;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Strategy {
+    // Most information on average.
+    Entropy,
+    // Smallest number of candidates left in the worst case.
+    WorstCase,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Suggestion {
+    word: String,
+    entropy: f64,
+    worst_case: usize,
+    is_candidate: bool,
+}
+
+impl Suggestion {
+    pub fn get_word(&self) -> &String {
+        &self.word
+    }
+
+    // Expected information of the guess, in bits.
+    pub fn get_entropy(&self) -> f64 {
+        self.entropy
+    }
+
+    // Most candidates that can be left after the guess.
+    pub fn get_worst_case(&self) -> usize {
+        self.worst_case
+    }
+
+    // Whether the guess itself could be the answer.
+    pub fn is_candidate(&self) -> bool {
+        self.is_candidate
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Hint {
+    candidates: Vec<String>,
+    suggestions: Vec<Suggestion>,
+}
+
+impl Hint {
+    pub fn get_candidates(&self) -> &Vec<String> {
+        &self.candidates
+    }
+
+    pub fn get_suggestions(&self) -> &Vec<Suggestion> {
+        &self.suggestions
+    }
+}
+
+// Returns the words that would have produced every feedback in `history`.
+pub fn filter_candidates(words: &[String], history: &[Feedback]) -> Vec<String> {
+    let played: Vec<(String, Pattern)> = history
+        .iter()
+        .map(|feedback| (feedback.get_guess(), feedback.get_pattern()))
+        .collect();
+
+    words.iter()
+         .filter(|word| played.iter().all(|(guess, pattern)| {
+             scoring::score_pattern(guess.as_bytes(), word.as_bytes()) == *pattern
+         }))
+         .cloned()
+         .collect()
+}
+
+// Ranks `guesses` from best to worst at telling `candidates` apart. Ties go to
+// guesses that could be the answer, then to alphabetical order.
+pub fn rank_guesses(guesses: &[String], candidates: &[String], strategy: Strategy) -> Vec<Suggestion> {
+    let word_length = match candidates.first() {
+        Some(candidate) => candidate.len(),
+        None => return Vec::new(),
+    };
+
+    let mut bucket_sizes: Vec<usize> = vec![0; 3usize.pow(word_length as u32)];
+    let mut suggestions: Vec<Suggestion> = Vec::with_capacity(guesses.len());
+
+    for guess in guesses {
+        let (entropy, worst_case) = split_candidates(guess, candidates, &mut bucket_sizes);
+
+        suggestions.push(Suggestion {
+            word: guess.clone(),
+            entropy,
+            worst_case,
+            is_candidate: candidates.binary_search(guess).is_ok(),
+        });
+    }
+
+    suggestions.sort_by(|a, b| {
+        let by_strategy = match strategy {
+            Strategy::Entropy => b.entropy.partial_cmp(&a.entropy).unwrap_or(Ordering::Equal),
+            Strategy::WorstCase => a.worst_case.cmp(&b.worst_case),
+        };
+
+        by_strategy
+            .then(b.is_candidate.cmp(&a.is_candidate))
+            .then(a.word.cmp(&b.word))
+    });
+
+    suggestions
+}
+
+// Finds the remaining candidates and the `count` best next guesses. In hard
+// mode, only guesses that use the revealed hints are suggested.
+pub fn get_hint(
+    wordbank: &WordBank,
+    history: &[Feedback],
+    hard_mode: bool,
+    strategy: Strategy,
+    count: usize,
+) -> Hint {
+    let candidates = filter_candidates(wordbank.get_unknown_words(), history);
+
+    let guesses: Vec<String> = if hard_mode {
+        wordbank.get_allowed_words()
+                .iter()
+                .filter(|guess| user_input::is_guess_using_hints(guess, history).is_ok())
+                .cloned()
+                .collect()
+    } else {
+        wordbank.get_allowed_words().clone()
+    };
+
+    let mut suggestions = rank_guesses(&guesses, &candidates, strategy);
+
+    suggestions.truncate(count);
+
+    Hint { candidates, suggestions }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct GuessAnalysis {
+    guess: String,
+    candidates_before: usize,
+    candidates_after: usize,
+    expected_bits: f64,
+    best_guess: Option<Suggestion>,
+}
+
+impl GuessAnalysis {
+    pub fn get_guess(&self) -> &String {
+        &self.guess
+    }
+
+    pub fn get_candidates_before(&self) -> usize {
+        self.candidates_before
+    }
+
+    pub fn get_candidates_after(&self) -> usize {
+        self.candidates_after
+    }
+
+    // Information the feedback actually gave, in bits.
+    pub fn get_bits_gained(&self) -> f64 {
+        if self.candidates_after == 0 {
+            return 0.0;
+        }
+
+        (self.candidates_before as f64 / self.candidates_after as f64).log2()
+    }
+
+    // Information the guess was expected to give before it was played.
+    pub fn get_expected_bits(&self) -> f64 {
+        self.expected_bits
+    }
+
+    // What the solver would have played instead.
+    pub fn get_best_guess(&self) -> Option<&Suggestion> {
+        self.best_guess.as_ref()
+    }
+}
+
+// Replays a finished game and rates every guess against the candidates that
+// were left at the time it was played.
+pub fn analyze_game(wordbank: &WordBank, history: &[Feedback], hard_mode: bool) -> Vec<GuessAnalysis> {
+    let mut candidates = wordbank.get_unknown_words().clone();
+    let mut analysis: Vec<GuessAnalysis> = Vec::with_capacity(history.len());
+
+    for (i, feedback) in history.iter().enumerate() {
+        let guess = feedback.get_guess();
+        let hint = get_hint(wordbank, &history[..i], hard_mode, Strategy::Entropy, 1);
+
+        let mut bucket_sizes: Vec<usize> = vec![0; 3usize.pow(guess.len() as u32)];
+        let (expected_bits, _) = split_candidates(&guess, &candidates, &mut bucket_sizes);
+
+        let candidates_before = candidates.len();
+
+        candidates = filter_candidates(&candidates, std::slice::from_ref(feedback));
+
+        analysis.push(GuessAnalysis {
+            guess,
+            candidates_before,
+            candidates_after: candidates.len(),
+            expected_bits,
+            best_guess: hint.suggestions.into_iter().next(),
+        });
+    }
+
+    analysis
+}
+
+// Plays the guessing side of the game, for a word that someone else keeps in
+// mind and scores by hand.
+pub struct Guesser<'a> {
+    wordbank: &'a WordBank,
+    candidates: Vec<String>,
+    history: Vec<Feedback>,
+}
+
+impl<'a> Guesser<'a> {
+    pub fn new(wordbank: &'a WordBank) -> Guesser<'a> {
+        Guesser { wordbank, candidates: wordbank.get_unknown_words().clone(), history: Vec::new() }
+    }
+
+    pub fn get_candidates(&self) -> &Vec<String> {
+        &self.candidates
+    }
+
+    pub fn get_history(&self) -> &Vec<Feedback> {
+        &self.history
+    }
+
+    // The guess that tells the remaining candidates apart best. With one or two
+    // candidates left, that is always one of them.
+    pub fn next_guess(&self) -> String {
+        let suggestions = rank_guesses(self.wordbank.get_allowed_words(), &self.candidates, Strategy::Entropy);
+
+        suggestions[0].word.clone()
+    }
+
+    // Takes in the feedback for a guess. Feedback that no word in the list could
+    // have given is rejected and forgotten, so it can be entered again.
+    pub fn add_feedback(&mut self, feedback: Feedback) -> Result<(), AppError> {
+        let candidates = filter_candidates(&self.candidates, std::slice::from_ref(&feedback));
+
+        if candidates.is_empty() {
+            return Err(AppError::NoMatchingWordErr);
+        }
+
+        self.candidates = candidates;
+        self.history.push(feedback);
+
+        Ok(())
+    }
+}
+
+// Scores the guess against every candidate, and returns the entropy of the
+// resulting feedback patterns and the size of the largest group of candidates
+// sharing a pattern.
+fn split_candidates(guess: &str, candidates: &[String], bucket_sizes: &mut [usize]) -> (f64, usize) {
+    let patterns: Vec<Pattern> = scoring::score_patterns(guess, candidates);
+
+    for &pattern in &patterns {
+        bucket_sizes[pattern as usize] += 1;
+    }
+
+    let total = candidates.len() as f64;
+    let mut entropy = 0.0;
+    let mut worst_case = 0;
+
+    for pattern in patterns {
+        let size = bucket_sizes[pattern as usize];
+
+        // Each pattern is only counted the first time it is seen.
+        if size == 0 {
+            continue;
+        }
+
+        let p = size as f64 / total;
+
+        entropy -= p * p.log2();
+        worst_case = worst_case.max(size);
+        bucket_sizes[pattern as usize] = 0;
+    }
+
+    (entropy, worst_case)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn to_strings(words: &[&str]) -> Vec<String> {
+        words.iter().map(|word| word.to_string()).collect()
+    }
+
+    #[test]
+    fn test_filter_candidates() {
+        let words = to_strings(&["catch", "hello", "hells", "shell", "zebra"]);
+        let history = vec![scoring::score("shell", "hello")];
+
+        assert_eq!(to_strings(&["hello"]), filter_candidates(&words, &history));
+        assert_eq!(words, filter_candidates(&words, &[]));
+    }
+
+    #[test]
+    fn test_rank_guesses() {
+        let candidates = to_strings(&["batch", "catch", "hatch", "latch", "match"]);
+        let guesses = to_strings(&["fuzzy", "batch", "chomp"]);
+
+        // "chomp" splits the candidates 3/1/1, "batch" 4/1, and "fuzzy" not at all.
+        let ranked = rank_guesses(&guesses, &candidates, Strategy::Entropy);
+        let words: Vec<&String> = ranked.iter().map(|s| s.get_word()).collect();
+
+        assert_eq!(vec!["chomp", "batch", "fuzzy"], words);
+        assert_eq!(vec![3, 4, 5], ranked.iter().map(|s| s.get_worst_case()).collect::<Vec<_>>());
+        assert!(ranked[1].is_candidate());
+        assert_eq!(0.0, ranked[2].get_entropy());
+
+        let ranked = rank_guesses(&guesses, &candidates, Strategy::WorstCase);
+        assert_eq!("chomp", ranked[0].get_word());
+    }
+
+    #[test]
+    fn test_rank_guesses_single_candidate() {
+        let candidates = to_strings(&["hello"]);
+        let guesses = to_strings(&["catch", "hello", "zebra"]);
+
+        let ranked = rank_guesses(&guesses, &candidates, Strategy::Entropy);
+
+        assert_eq!("hello", ranked[0].get_word());
+        assert_eq!(0.0, ranked[0].get_entropy());
+    }
+
+    #[test]
+    fn test_analyze_game() {
+        let wordbank = WordBank::init_with_length(8);
+        let history = vec![
+            scoring::score("absorbed", "abortive"),
+            scoring::score("abortive", "abortive"),
+        ];
+
+        let analysis = analyze_game(&wordbank, &history, false);
+
+        assert_eq!(2, analysis.len());
+        assert_eq!(wordbank.get_unknown_words().len(), analysis[0].get_candidates_before());
+        assert_eq!(analysis[0].get_candidates_after(), analysis[1].get_candidates_before());
+        assert_eq!(1, analysis[1].get_candidates_after());
+        assert!(analysis[0].get_bits_gained() > 0.0);
+        assert!(analysis[0].get_expected_bits() <= analysis[0].get_best_guess().unwrap().get_entropy());
+    }
+
+    #[test]
+    fn test_guesser() {
+        let wordbank = WordBank::init_with_length(8);
+
+        for answer in ["abortive", "absorbed"] {
+            let mut guesser = Guesser::new(&wordbank);
+
+            loop {
+                let guess = guesser.next_guess();
+                let feedback = scoring::score(&guess, answer);
+
+                guesser.add_feedback(feedback.clone()).unwrap();
+
+                if feedback.is_win() {
+                    break;
+                }
+
+                assert!(guesser.get_history().len() < 10);
+            }
+
+            assert_eq!(&vec![answer.to_string()], guesser.get_candidates());
+        }
+    }
+
+    #[test]
+    fn test_guesser_rejects_inconsistent_feedback() {
+        let wordbank = WordBank::init();
+        let mut guesser = Guesser::new(&wordbank);
+
+        guesser.add_feedback(scoring::score("shell", "hello")).unwrap();
+        let candidates = guesser.get_candidates().clone();
+
+        // Only "hello" is left, and it cannot score "hello" as all gray.
+        assert_eq!(
+            Err(AppError::NoMatchingWordErr),
+            guesser.add_feedback(scoring::score("hello", "catch"))
+        );
+        assert_eq!(&candidates, guesser.get_candidates());
+        assert_eq!(1, guesser.get_history().len());
+    }
+
+    #[test]
+    fn test_get_hint() {
+        let wordbank = WordBank::init_with_length(8);
+        let history = vec![scoring::score("absorbed", "abortive")];
+
+        let hint = get_hint(&wordbank, &history, true, Strategy::Entropy, 3);
+
+        assert!(hint.get_candidates().contains(&"abortive".to_string()));
+        assert!(hint.get_suggestions().len() <= 3);
+
+        for suggestion in hint.get_suggestions() {
+            assert!(user_input::is_guess_using_hints(suggestion.get_word(), &history).is_ok());
+        }
+    }
+}
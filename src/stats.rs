@@ -0,0 +1,236 @@
+// Per-player statistics that are kept between runs: games played, wins,
+// streaks and how many guesses each win took.
+
+use std::fs;
+use std::path::PathBuf;
+
+use crate::store;
+use crate::utils;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct PlayerStats {
+    username: String,
+    played: u32,
+    wins: u32,
+    current_streak: u32,
+    max_streak: u32,
+    // The i-th element counts the games won in i+1 guesses.
+    guess_distribution: Vec<u32>,
+}
+
+impl PlayerStats {
+    pub fn new(username: String) -> PlayerStats {
+        PlayerStats {
+            username,
+            played: 0,
+            wins: 0,
+            current_streak: 0,
+            max_streak: 0,
+            guess_distribution: Vec::new(),
+        }
+    }
+
+    pub fn get_username(&self) -> &String {
+        &self.username
+    }
+
+    pub fn get_played(&self) -> u32 {
+        self.played
+    }
+
+    pub fn get_wins(&self) -> u32 {
+        self.wins
+    }
+
+    pub fn get_current_streak(&self) -> u32 {
+        self.current_streak
+    }
+
+    pub fn get_max_streak(&self) -> u32 {
+        self.max_streak
+    }
+
+    pub fn get_guess_distribution(&self) -> &Vec<u32> {
+        &self.guess_distribution
+    }
+
+    pub fn get_win_percentage(&self) -> u32 {
+        if self.played == 0 {
+            return 0;
+        }
+
+        ((self.wins as f64 / self.played as f64) * 100.0).round() as u32
+    }
+
+    // `guesses` is the number of guesses it took to win, or `None` on a loss.
+    pub fn record_game(&mut self, guesses: Option<usize>) {
+        self.played += 1;
+
+        match guesses {
+            Some(guesses) => {
+                assert_ne!(0, guesses);
+
+                self.wins += 1;
+                self.current_streak += 1;
+                self.max_streak = self.max_streak.max(self.current_streak);
+
+                if self.guess_distribution.len() < guesses {
+                    self.guess_distribution.resize(guesses, 0);
+                }
+
+                self.guess_distribution[guesses - 1] += 1;
+            }
+            None => self.current_streak = 0,
+        }
+    }
+
+    fn to_line(&self) -> String {
+        let distribution: Vec<String> = self.guess_distribution
+            .iter()
+            .map(|count| count.to_string())
+            .collect();
+
+        format!(
+            "{} {} {} {} {} {}",
+            self.played, self.wins, self.current_streak, self.max_streak,
+            distribution.join(","), self.username
+        )
+    }
+
+    fn from_line(line: &str) -> Option<PlayerStats> {
+        let fields: Vec<&str> = line.splitn(6, ' ').collect();
+
+        if fields.len() != 6 || fields[5].is_empty() {
+            return None;
+        }
+
+        let guess_distribution = if fields[4].is_empty() {
+            Vec::new()
+        } else {
+            fields[4].split(',')
+                     .map(|count| count.parse().ok())
+                     .collect::<Option<Vec<u32>>>()?
+        };
+
+        Some(PlayerStats {
+            username: fields[5].to_string(),
+            played: fields[0].parse().ok()?,
+            wins: fields[1].parse().ok()?,
+            current_streak: fields[2].parse().ok()?,
+            max_streak: fields[3].parse().ok()?,
+            guess_distribution,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct Stats {
+    players: Vec<PlayerStats>,
+    stats_text_path: PathBuf,
+}
+
+impl Stats {
+    pub fn init() -> Stats {
+        let mut stats_text_path = utils::get_data_dir();
+
+        stats_text_path.push("stats.txt");
+
+        let players = match fs::read_to_string(&stats_text_path) {
+            Ok(stats_str) => parse_players(&stats_str),
+            Err(_) => Vec::new(),
+        };
+
+        Stats { players, stats_text_path }
+    }
+
+    pub fn get_players(&self) -> &Vec<PlayerStats> {
+        &self.players
+    }
+
+    pub fn get_player_stats(&self, username: &str) -> Option<&PlayerStats> {
+        self.players.iter().find(|player| player.username == username)
+    }
+
+    pub fn record_game(&mut self, username: &str, guesses: Option<usize>) {
+        self.players = store::update_entries(
+            &self.stats_text_path,
+            &self.players,
+            parse_players,
+            |players| record_player_game(players, username, guesses),
+            to_text,
+        );
+    }
+}
+
+// Lines that cannot be parsed are dropped rather than stopping the game.
+fn parse_players(stats_str: &str) -> Vec<PlayerStats> {
+    stats_str.lines().filter_map(PlayerStats::from_line).collect()
+}
+
+fn to_text(players: &[PlayerStats]) -> String {
+    let mut buf = String::new();
+
+    for player in players {
+        buf.push_str(&player.to_line());
+        buf.push('\n');
+    }
+
+    buf
+}
+
+fn record_player_game(players: &mut Vec<PlayerStats>, username: &str, guesses: Option<usize>) {
+    let pos = match players.iter().position(|player| player.username == username) {
+        Some(pos) => pos,
+        None => {
+            players.push(PlayerStats::new(username.to_string()));
+            players.len() - 1
+        }
+    };
+
+    players[pos].record_game(guesses);
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_game() {
+        let mut stats = PlayerStats::new("abcde".to_string());
+
+        stats.record_game(Some(3));
+        stats.record_game(Some(4));
+        stats.record_game(Some(3));
+        stats.record_game(None);
+        stats.record_game(Some(1));
+
+        assert_eq!(5, stats.get_played());
+        assert_eq!(4, stats.get_wins());
+        assert_eq!(80, stats.get_win_percentage());
+        assert_eq!(1, stats.get_current_streak());
+        assert_eq!(3, stats.get_max_streak());
+        assert_eq!(&vec![1, 0, 2, 1], stats.get_guess_distribution());
+    }
+
+    #[test]
+    fn test_win_percentage_without_games() {
+        assert_eq!(0, PlayerStats::new("abcde".to_string()).get_win_percentage());
+    }
+
+    #[test]
+    fn test_player_stats_line() {
+        let mut stats = PlayerStats::new("a b c".to_string());
+
+        assert_eq!(Some(stats.clone()), PlayerStats::from_line(&stats.to_line()));
+
+        stats.record_game(Some(2));
+        stats.record_game(None);
+
+        assert_eq!(Some(stats.clone()), PlayerStats::from_line(&stats.to_line()));
+
+        for line in ["", "1 1 1 1 1", "1 1 1 1 x,1 abcde", "one 1 1 1 1 abcde"] {
+            assert_eq!(None, PlayerStats::from_line(line));
+        }
+    }
+}
@@ -0,0 +1,176 @@
+// Safe writes for the files that several games may share, e.g. when a few are
+// running in different terminals at once.
+//
+// A file is never truncated in place: the new contents go to a temporary file
+// next to it, which then replaces it, so a crash leaves either the old or the
+// new contents. Changes are made while holding a lock on a separate ".lock"
+// file, and are applied to what is on disk at that moment rather than to what
+// was read at the start of the game.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+
+// Reads the file (empty if it does not exist yet), then replaces it with what
+// `update` returns, without any other game changing it in between.
+pub fn update_file<F>(path: &Path, update: F) -> io::Result<()>
+where
+    F: FnOnce(&str) -> String,
+{
+    let lock = lock_file(path)?;
+
+    let current = match fs::read_to_string(path) {
+        Ok(current) => current,
+        Err(err) if err.kind() == ErrorKind::NotFound => String::new(),
+        Err(err) => return Err(err),
+    };
+
+    let result = write_atomically(path, &update(&current));
+
+    lock.unlock()?;
+    result
+}
+
+// Reads the entries of the file as they are now, applies the change to them
+// and writes them back, so that the entries other games added in the meantime
+// are kept. Returns the updated entries. If the file cannot be updated, the
+// change is applied to the entries the game already had, so that it can go on.
+pub fn update_entries<T, P, A, S>(path: &Path, known: &[T], parse: P, apply: A, serialize: S) -> Vec<T>
+where
+    T: Clone,
+    P: FnOnce(&str) -> Vec<T>,
+    A: Fn(&mut Vec<T>),
+    S: FnOnce(&[T]) -> String,
+{
+    let mut entries: Vec<T> = Vec::new();
+
+    let result = update_file(path, |current| {
+        entries = parse(current);
+        apply(&mut entries);
+        serialize(&entries)
+    });
+
+    if let Err(err) = result {
+        eprintln!("IO Error: {}", err);
+
+        entries = known.to_vec();
+        apply(&mut entries);
+    }
+
+    entries
+}
+
+// Replaces the file with the contents in one step.
+pub fn write_atomically(path: &Path, contents: &str) -> io::Result<()> {
+    let temp_path = get_sibling_path(path, &format!("{}.tmp", process::id()));
+
+    let result = File::create(&temp_path)
+        .and_then(|mut temp_file| {
+            temp_file.write_all(contents.as_bytes())?;
+            temp_file.sync_all()
+        })
+        .and_then(|()| fs::rename(&temp_path, path));
+
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+
+    result
+}
+
+// Blocks until no other game holds the lock of the file. The lock is released
+// when the returned file is unlocked or dropped.
+fn lock_file(path: &Path) -> io::Result<File> {
+    let lock = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(get_sibling_path(path, "lock"))?;
+
+    lock.lock()?;
+    Ok(lock)
+}
+
+// e.g. highscore.txt -> highscore.txt.lock
+fn get_sibling_path(path: &Path, extension: &str) -> PathBuf {
+    let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+
+    file_name.push(".");
+    file_name.push(extension);
+    path.with_file_name(file_name)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{env, thread};
+
+    fn get_temp_dir(name: &str) -> PathBuf {
+        let temp_dir = env::temp_dir().join(format!("wordle_clone_{}_{}", name, process::id()));
+
+        fs::create_dir_all(&temp_dir).unwrap();
+        temp_dir
+    }
+
+    #[test]
+    fn test_write_atomically() {
+        let temp_dir = get_temp_dir("write");
+        let path = temp_dir.join("scores.txt");
+
+        write_atomically(&path, "first\n").unwrap();
+        write_atomically(&path, "second\n").unwrap();
+
+        assert_eq!("second\n", fs::read_to_string(&path).unwrap());
+        assert_eq!(1, fs::read_dir(&temp_dir).unwrap().count());
+
+        fs::remove_dir_all(temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_update_file_concurrently() {
+        let temp_dir = get_temp_dir("update");
+        let path = temp_dir.join("count.txt");
+
+        let threads: Vec<_> = (0..4)
+            .map(|_| {
+                let path = path.clone();
+
+                thread::spawn(move || {
+                    for _ in 0..25 {
+                        update_file(&path, |current| {
+                            let count: u32 = current.trim().parse().unwrap_or(0);
+                            (count + 1).to_string()
+                        }).unwrap();
+                    }
+                })
+            })
+            .collect();
+
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        assert_eq!("100", fs::read_to_string(&path).unwrap());
+
+        fs::remove_dir_all(temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_update_entries_without_file() {
+        let path = get_temp_dir("entries").join("missing").join("list.txt");
+
+        let entries = update_entries(
+            &path,
+            &[1, 2],
+            |current| current.lines().filter_map(|line| line.parse().ok()).collect(),
+            |entries: &mut Vec<u32>| entries.push(3),
+            |entries| entries.iter().map(|entry| format!("{}\n", entry)).collect(),
+        );
+
+        assert_eq!(vec![1, 2, 3], entries);
+
+        fs::remove_dir_all(get_temp_dir("entries")).unwrap();
+    }
+}
@@ -1,77 +1,24 @@
-use std::collections::HashMap;
 use std::io;
 
-use crate::display::{GuessColorMapping, FontColors, Display};
-use crate::constants::WORD_LENGTH;
 use crate::wordbank::WordBank;
+use crate::scoring::{Feedback, LetterScore};
 use crate::err::AppError;
 use crate::utils;
 
 pub type IfWinner = bool;
 
-// Returns a lower-cased version of the validated user input.
-pub fn get_user_guess(wordbank: &WordBank) -> Result<String, io::Error> {
-    let guess: String = loop {
-        println!("Your guess: ");
+// Returns the user's guess as typed, minus the surrounding whitespace. The
+// guess is validated once it is submitted to the game.
+pub fn get_user_guess() -> Result<String, io::Error> {
+    println!("Your guess (or ? for a hint): ");
 
-        let mut guess = String::new();
+    let mut guess = String::new();
 
-        io::stdin().read_line(&mut guess)?;
-
-        if let Err(err) = is_guess_valid(&mut guess, wordbank) {
-            eprintln!("\n{}", err);
-            continue;
-        }
-
-        println!("{}", guess);
-        break guess;
-    };
-
-    Ok(guess)
-}
-
-// Maps each letter of the user's guess to the three colors that represents
-// how correct they are with respect to the letters of the unknown word. 
-// This also checks if the user correctly guesses the unknown word.
-pub fn process_guess(
-        guess: &str, 
-        unknown_word: &str, 
-        display: &mut Display,
-) -> (GuessColorMapping, IfWinner) {
-    assert_eq!(guess.to_lowercase().as_str(), guess);
-    assert_eq!(unknown_word.to_lowercase().as_str(), unknown_word);
-
-    let mut gcm: GuessColorMapping = [(' ', FontColors::Gray); WORD_LENGTH];
-    let mut ifwinner = false;
-
-    let mut letter_occurrences: HashMap<char, u8> = HashMap::new();
-
-    for letter in unknown_word.chars() {
-        *letter_occurrences.entry(letter).or_insert(0) += 1;
-    }
-
-    let guess_arr: Vec<char> = guess.chars().collect();
-    let unknown_word_arr: Vec<char> = unknown_word.chars().collect();
-
-    assert_eq!(guess_arr.len(), unknown_word_arr.len());
-
-    color_correct_guess_letters(
-        &guess_arr, &unknown_word_arr, 
-        &mut letter_occurrences, &mut gcm, 
-        &mut ifwinner
-    );
-
-    color_other_guess_letters(
-        &guess_arr, &unknown_word_arr, 
-        &mut letter_occurrences, display,
-        &mut gcm
-    );
-
-    for letter_color_mapping in gcm {
-        assert_ne!((' ', FontColors::Gray), letter_color_mapping);
+    if io::stdin().read_line(&mut guess)? == 0 {
+        return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "No more input to read."));
     }
 
-    (gcm, ifwinner)
+    Ok(guess.trim().to_string())
 }
 
 pub fn get_username() -> String {
@@ -94,93 +41,112 @@ pub fn get_username() -> String {
     username
 }
 
-fn is_guess_valid(
+// Returns the feedback the user gives for the program's guess, as typed.
+pub fn get_user_feedback(guess: &str) -> Result<String, io::Error> {
+    println!(
+        "Feedback for {} (g = green, y = yellow, . = gray): ", 
+        guess.to_uppercase()
+    );
+
+    let mut feedback = String::new();
+
+    if io::stdin().read_line(&mut feedback)? == 0 {
+        return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "No more input to read."));
+    }
+
+    Ok(feedback.trim().to_string())
+}
+
+// Reads feedback typed like "gy..g" for the given guess, one character per
+// letter: g for green, y for yellow and . for gray.
+pub fn parse_feedback(guess: &str, input: &str) -> Result<Feedback, AppError> {
+    let input = input.trim().to_lowercase();
+    let word_length = guess.chars().count();
+
+    if input.chars().count() != word_length {
+        return Err(AppError::InvalidFeedbackErr(word_length));
+    }
+
+    let letters = guess.chars()
+        .zip(input.chars())
+        .map(|(letter, c)| match c {
+            'g' => Ok((letter, LetterScore::Correct)),
+            'y' => Ok((letter, LetterScore::Present)),
+            '.' => Ok((letter, LetterScore::Absent)),
+            _ => Err(AppError::InvalidFeedbackErr(word_length)),
+        })
+        .collect::<Result<Vec<_>, AppError>>()?;
+
+    Ok(Feedback::new(letters))
+}
+
+// Normalizes the guess in place and checks it against the word bank.
+pub(crate) fn is_guess_valid(
         guess: &mut String, 
         wordbank: &WordBank
-        ) -> Result<(), String> {  
+        ) -> Result<(), AppError> {  
     *guess = guess.trim().to_string();
 
-    if guess.len() != WORD_LENGTH {
-        return Err(AppError::InvalidGuessLengthErr.to_str::<()>(None));
+    if guess.chars().count() != wordbank.get_word_length() {
+        return Err(AppError::InvalidGuessLengthErr(wordbank.get_word_length()));
     }
 
     if !guess.is_ascii() || !each_char_is_alpha(guess) {
-        return Err(AppError::NonAlphaGuessErr.to_str::<()>(None));
+        return Err(AppError::NonAlphaGuessErr);
     }
 
     *guess = guess.to_lowercase();
 
     if !wordbank.in_allowed_words(guess) {
-        return Err(AppError::NotEnglishGuessErr.to_str::<()>(None));
+        return Err(AppError::NotEnglishGuessErr);
     }
 
     Ok(())
 }
 
-fn each_char_is_alpha(string: &str) -> bool {
-    string.chars()
-          .all(|c| matches!(c, 'a'..='z' | 'A'..='Z'))
-}
+// Checks a normalized guess against the hints revealed by earlier guesses, as
+// required by hard mode: every green letter must stay in its position, and
+// every green or blue letter must be reused at least as many times as it was
+// revealed.
+pub(crate) fn is_guess_using_hints(guess: &str, history: &[Feedback]) -> Result<(), AppError> {
+    let guess_arr: Vec<char> = guess.chars().collect();
 
-// If the i-th letter in the user's guess matches the i-th letter of the unknown
-// word, this maps the letter to color GREEN.
-fn color_correct_guess_letters(
-    guess_arr: &[char],
-    unknown_word_arr: &[char],
-    letter_occurrences: &mut HashMap<char, u8>,
-    gcm: &mut GuessColorMapping,
-    ifwinner: &mut IfWinner
-) {
-    let mut correct_letters = 0;
-
-    for i in 0..guess_arr.len() {
-        if guess_arr[i] == unknown_word_arr[i] {
-            let correct: char = guess_arr[i];
-
-            *letter_occurrences.entry(correct).or_insert(0) -= 1;
-            gcm[i] = (correct.to_ascii_uppercase(), FontColors::Green);
-            correct_letters += 1;
+    for feedback in history {
+        for (i, &(letter, score)) in feedback.get_letters().iter().enumerate() {
+            if score == LetterScore::Correct && guess_arr[i] != letter {
+                return Err(AppError::HardModeMisplacedLetterErr(i + 1, letter));
+            }
         }
-    }
-    if correct_letters == WORD_LENGTH {
-        *ifwinner = true;
-    }
-}
 
-// If the i-th letter in the user's guess matches with a j-th letter in the unknown
-// word, where i != j, then this maps the letter to color BLUE. But if the i-th
-// letter in the user's guess does not match ANY letter of the unknown word, then
-// this just maps it to gray (or basically not changing its color).
-fn color_other_guess_letters(
-    guess_arr: &[char],
-    unknown_word_arr: &[char],
-    letter_occurrences: &mut HashMap<char, u8>,
-    display: &mut Display,
-    gcm: &mut GuessColorMapping,
-) {
-    for i in 0..guess_arr.len() {
-        let incorrect: char = guess_arr[i];
-        
-        if incorrect != unknown_word_arr[i] {
-            if unknown_word_arr.contains(&incorrect) && letter_occurrences[&incorrect] > 0 {
-                *letter_occurrences.entry(incorrect)
-                                .or_insert(0) -= 1;
-                gcm[i] = (incorrect.to_ascii_uppercase(), FontColors::Blue);
-            } else {
-                gcm[i] = (incorrect.to_ascii_uppercase(), FontColors::Gray);     
-                
-                if !display.get_invalid_letters().contains(&incorrect) {
-                    display.update_invalid_letters(incorrect.to_ascii_uppercase());
-                }
+        for &(letter, score) in feedback.get_letters() {
+            if score == LetterScore::Absent {
+                continue;
+            }
+
+            let revealed = feedback.get_letters()
+                .iter()
+                .filter(|&&(l, s)| l == letter && s != LetterScore::Absent)
+                .count();
+            let used = guess_arr.iter().filter(|&&l| l == letter).count();
+
+            if used < revealed {
+                return Err(AppError::HardModeMissingLetterErr(letter, revealed));
             }
         }
     }
+
+    Ok(())
 }
 
+fn each_char_is_alpha(string: &str) -> bool {
+    string.chars()
+          .all(|c| c.is_ascii_alphabetic())
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::scoring;
 
     #[test]
     fn test_is_guess_valid() {
@@ -196,9 +162,9 @@ mod tests {
         for (i, word) in guess.iter().enumerate() {
             assert_eq!(
                 match i {
-                    0   => Err(AppError::InvalidGuessLengthErr.to_str::<()>(None)),
-                    1|2 => Err(AppError::NonAlphaGuessErr.to_str::<()>(None)),
-                    3   => Err(AppError::NotEnglishGuessErr.to_str::<()>(None)),
+                    0   => Err(AppError::InvalidGuessLengthErr(5)),
+                    1|2 => Err(AppError::NonAlphaGuessErr),
+                    3   => Err(AppError::NotEnglishGuessErr),
                     4   => Ok(()),
                     _   => panic!("Should not reach here!"),
                 },
@@ -207,6 +173,33 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_is_guess_using_hints() {
+        let history = vec![scoring::score("crane", "caper")];
+
+        assert_eq!(Ok(()), is_guess_using_hints("caper", &history));
+        assert_eq!(Ok(()), is_guess_using_hints("cream", &history));
+        assert_eq!(
+            Err(AppError::HardModeMisplacedLetterErr(1, 'c')), 
+            is_guess_using_hints("racer", &history)
+        );
+        assert_eq!(
+            Err(AppError::HardModeMissingLetterErr('r', 1)), 
+            is_guess_using_hints("cable", &history)
+        );
+        assert_eq!(Ok(()), is_guess_using_hints("anything", &[]));
+    }
+
+    #[test]
+    fn test_parse_feedback() {
+        assert_eq!(Ok(scoring::score("shell", "hello")), parse_feedback("shell", ".yygy"));
+        assert_eq!(Ok(scoring::score("hello", "hello")), parse_feedback("hello", " GGGGG\n"));
+
+        for input in ["", "gy.g", "gy..gg", "gy..x", "gy. g"] {
+            assert_eq!(Err(AppError::InvalidFeedbackErr(5)), parse_feedback("shell", input));
+        }
+    }
+
     #[test]
     fn test_each_char_is_alpha() {
         let valid_strings: [&str; 5] = [
@@ -233,90 +226,4 @@ mod tests {
             assert!(!each_char_is_alpha(string));
         }
     }
-
-    #[test]
-    fn test_process_guess1() {
-        let mut display = Display::init();
-
-        let guess: [&str; 5] = [
-            "hello",
-            "catch",
-            "swear",
-            "slick",
-            "zebra"
-        ];
-        
-        let unknown_word: [&str; 5] = [
-            "hello",
-            "catch",
-            "swear",
-            "slick",
-            "zebra"
-        ];
-
-        for i in 0..guess.len() {
-            let guess = guess[i].to_string();
-            let unknown_word = unknown_word[i].to_string();
-
-            let (gcm, ifwinner) = process_guess(&guess, &unknown_word, &mut display);
-            let color_vec = get_color_vec(&gcm);
-            
-            assert_eq!(
-                (vec![FontColors::Green; WORD_LENGTH], true), 
-                (color_vec, ifwinner)
-            );
-        }
-    }
-
-    #[test]
-    fn test_process_guess2() {
-        let mut display = Display::init();
-
-        let guess = "tenet".to_string();
-        let unknown_word = "catch".to_string();
-        
-        let (gcm, ifwinner) = process_guess(&guess, &unknown_word, &mut display);
-        let color_vec = get_color_vec(&gcm);
-
-        assert_eq!(
-            (vec![
-                FontColors::Blue,
-                FontColors::Gray,
-                FontColors::Gray,
-                FontColors::Gray,
-                FontColors::Gray,
-            ], false),
-            (color_vec, ifwinner)
-        );
-    }
-
-    #[test]
-    fn test_process_guess3() {
-        let mut display = Display::init();
-
-        let guess = "shell".to_string();
-        let unknown_word = "hello".to_string();
-
-        let (gcm, ifwinner) = process_guess(&guess, &unknown_word, &mut display);
-        let color_vec = get_color_vec(&gcm);
-
-        assert_eq!(
-            (vec![
-                FontColors::Gray,
-                FontColors::Blue,
-                FontColors::Blue,
-                FontColors::Green,
-                FontColors::Blue,
-            ], false),
-            (color_vec, ifwinner)
-        );
-    }
-
-    fn get_color_vec(gcm: &GuessColorMapping) -> Vec<FontColors> {
-        let color_vec: Vec<FontColors> = gcm.iter()
-                                            .map(|tup| tup.1)
-                                            .collect();
-
-        color_vec
-    }
 }
\ No newline at end of file
@@ -1,4 +1,6 @@
-use std::{thread, time};
+use std::{env, fs, thread, time};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 
 pub fn sleep_sec(sec: u64) {
     let sec = time::Duration::from_secs(sec);
@@ -10,6 +12,237 @@ pub fn sleep_ms(ms: u64) {
     thread::sleep(ms);
 }
 
+// Escape sequences are left out along with the colors, e.g. with --no-color,
+// so screens follow each other instead.
 pub fn clear_screen() {
-    println!("{esc}[2J{esc}[1;1H", esc = 27 as char);
-}
\ No newline at end of file
+    if colored::control::SHOULD_COLORIZE.should_colorize() {
+        println!("{esc}[2J{esc}[1;1H", esc = 27 as char);
+    } else {
+        println!();
+    }
+}
+
+// The data (scores, statistics and the saved round) and the configuration
+// (word packs) live in the XDG directories, e.g. ~/.local/share/wordle_clone
+// and ~/.config/wordle_clone. Both can be moved to a single directory with
+// --data-dir or the WORDLE_CLONE_DIR variable.
+pub const DIR_ENV_VAR: &str = "WORDLE_CLONE_DIR";
+
+const APP_DIR_NAME: &str = "wordle_clone";
+const LEGACY_DIR_NAME: &str = "wc_resources";
+const LEGACY_DATA_FILES: [&str; 4] = ["highscore.txt", "stats.txt", "daily.txt", "saved_game.txt"];
+
+static DIR_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
+// Takes precedence over everything else. Only the first call has an effect.
+pub fn set_dir_override(dir: PathBuf) {
+    let _ = DIR_OVERRIDE.set(dir);
+}
+
+// Returns the directory where the game keeps its data, creating it if needed.
+// Files from the directory next to the executable, used by older versions, are
+// copied over the first time.
+pub fn get_data_dir() -> PathBuf {
+    let data_dir = get_dir("XDG_DATA_HOME", ".local/share");
+
+    prepare_data_dir(&data_dir, get_legacy_dir().as_deref());
+    data_dir
+}
+
+// Returns the directory where the game looks for its configuration, creating
+// it if needed. Word packs of older versions are copied over like the data.
+pub fn get_config_dir() -> PathBuf {
+    let config_dir = get_dir("XDG_CONFIG_HOME", ".config");
+
+    prepare_config_dir(&config_dir, get_legacy_dir().as_deref());
+    config_dir
+}
+
+// The data and the configuration are copied separately, with a marker file
+// each, as they share a directory with --data-dir or WORDLE_CLONE_DIR.
+const LEGACY_DATA_MARKER: &str = ".copied_legacy_data";
+const LEGACY_CONFIG_MARKER: &str = ".copied_legacy_config";
+
+fn prepare_data_dir(data_dir: &Path, legacy_dir: Option<&Path>) {
+    create_dir(data_dir);
+
+    if let Some(legacy_dir) = legacy_dir {
+        let file_paths: Vec<PathBuf> = LEGACY_DATA_FILES.iter().map(PathBuf::from).collect();
+
+        copy_legacy_files(legacy_dir, data_dir, LEGACY_DATA_MARKER, &file_paths);
+    }
+}
+
+fn prepare_config_dir(config_dir: &Path, legacy_dir: Option<&Path>) {
+    create_dir(config_dir);
+
+    if let Some(legacy_dir) = legacy_dir {
+        let pack_paths: Vec<PathBuf> = fs::read_dir(legacy_dir.join("packs"))
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| entry.ok().map(|entry| PathBuf::from("packs").join(entry.file_name())))
+            .collect();
+
+        copy_legacy_files(legacy_dir, config_dir, LEGACY_CONFIG_MARKER, &pack_paths);
+    }
+}
+
+// Copies the files the directory does not have yet, once: the marker file
+// keeps files that were deleted since from coming back.
+fn copy_legacy_files(legacy_dir: &Path, dir: &Path, marker: &str, relative_paths: &[PathBuf]) {
+    let marker_path = dir.join(marker);
+
+    if !legacy_dir.is_dir() || legacy_dir == dir || marker_path.exists() {
+        return;
+    }
+
+    for relative_path in relative_paths {
+        let legacy_path = legacy_dir.join(relative_path);
+        let path = dir.join(relative_path);
+
+        if !legacy_path.is_file() || path.exists() {
+            continue;
+        }
+
+        let result = path.parent().map_or(Ok(()), fs::create_dir_all)
+            .and_then(|()| fs::copy(&legacy_path, &path));
+
+        if let Err(err) = result {
+            eprintln!("Failed to copy {} from an older version: {}", legacy_path.display(), err);
+        }
+    }
+
+    if let Err(err) = fs::write(&marker_path, "") {
+        eprintln!("IO Error: {}", err);
+    }
+}
+
+fn get_dir(xdg_var: &str, home_fallback: &str) -> PathBuf {
+    if let Some(dir) = DIR_OVERRIDE.get() {
+        return dir.clone();
+    }
+
+    resolve_dir(xdg_var, home_fallback, |name| env::var_os(name).map(PathBuf::from))
+        .or_else(get_legacy_dir)
+        .expect("Failed to find a directory for the game's files. Set HOME or WORDLE_CLONE_DIR.")
+}
+
+// Relative XDG directories are ignored, as the XDG spec asks.
+fn resolve_dir<F>(xdg_var: &str, home_fallback: &str, get_var: F) -> Option<PathBuf>
+where
+    F: Fn(&str) -> Option<PathBuf>,
+{
+    let get_var = |name: &str| get_var(name).filter(|value| !value.as_os_str().is_empty());
+
+    if let Some(dir) = get_var(DIR_ENV_VAR) {
+        return Some(dir);
+    }
+
+    match (get_var(xdg_var).filter(|dir| dir.is_absolute()), get_var("HOME")) {
+        (Some(xdg_dir), _) => Some(xdg_dir.join(APP_DIR_NAME)),
+        (None, Some(home_dir)) => Some(home_dir.join(home_fallback).join(APP_DIR_NAME)),
+        (None, None) => None,
+    }
+}
+
+// The directory next to the executable, where older versions kept everything.
+fn get_legacy_dir() -> Option<PathBuf> {
+    let mut legacy_dir = env::current_exe().ok()?;
+
+    legacy_dir.pop();
+    legacy_dir.push(LEGACY_DIR_NAME);
+
+    Some(legacy_dir)
+}
+
+fn create_dir(dir: &Path) {
+    fs::create_dir_all(dir)
+        .unwrap_or_else(|err| panic!("Failed to create the directory {}: {}", dir.display(), err));
+}
+
+// 1 -> "1st", 2 -> "2nd", 11 -> "11th", and so on.
+pub fn ordinal(n: usize) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+
+    format!("{}{}", n, suffix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_dir() {
+        let vars = |set: &'static [(&'static str, &'static str)]| {
+            move |name: &str| set.iter().find(|(k, _)| *k == name).map(|(_, v)| PathBuf::from(v))
+        };
+
+        assert_eq!(
+            Some(PathBuf::from("/xdg/data/wordle_clone")),
+            resolve_dir("XDG_DATA_HOME", ".local/share", vars(&[("XDG_DATA_HOME", "/xdg/data"), ("HOME", "/home/a")]))
+        );
+        assert_eq!(
+            Some(PathBuf::from("/home/a/.local/share/wordle_clone")),
+            resolve_dir("XDG_DATA_HOME", ".local/share", vars(&[("XDG_DATA_HOME", ""), ("HOME", "/home/a")]))
+        );
+        assert_eq!(
+            Some(PathBuf::from("/home/a/.config/wordle_clone")),
+            resolve_dir("XDG_CONFIG_HOME", ".config", vars(&[("XDG_CONFIG_HOME", "relative"), ("HOME", "/home/a")]))
+        );
+        assert_eq!(
+            Some(PathBuf::from("/games/wordle")),
+            resolve_dir("XDG_DATA_HOME", ".local/share", vars(&[("WORDLE_CLONE_DIR", "/games/wordle"), ("HOME", "/home/a")]))
+        );
+        assert_eq!(None, resolve_dir("XDG_DATA_HOME", ".local/share", vars(&[])));
+    }
+
+    #[test]
+    fn test_config_dir_before_data_dir() {
+        let temp_dir = env::temp_dir().join(format!("wordle_clone_legacy_{}", std::process::id()));
+        let legacy_dir = temp_dir.join(LEGACY_DIR_NAME);
+        let shared_dir = temp_dir.join("shared");
+
+        fs::create_dir_all(legacy_dir.join("packs")).unwrap();
+        fs::write(legacy_dir.join("highscore.txt"), "abcde 3\n").unwrap();
+        fs::write(legacy_dir.join("packs").join("birds.txt"), "name = birds\n").unwrap();
+
+        // As when a pack is looked up before the high scores are read.
+        prepare_config_dir(&shared_dir, Some(&legacy_dir));
+        prepare_data_dir(&shared_dir, Some(&legacy_dir));
+
+        assert_eq!("abcde 3\n", fs::read_to_string(shared_dir.join("highscore.txt")).unwrap());
+        assert!(shared_dir.join("packs").join("birds.txt").is_file());
+
+        // Files deleted after the first time are not copied again.
+        fs::remove_file(shared_dir.join("highscore.txt")).unwrap();
+        prepare_data_dir(&shared_dir, Some(&legacy_dir));
+
+        assert!(!shared_dir.join("highscore.txt").exists());
+
+        fs::remove_dir_all(temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_ordinal() {
+        let expected: [(usize, &str); 8] = [
+            (1, "1st"),
+            (2, "2nd"),
+            (3, "3rd"),
+            (4, "4th"),
+            (11, "11th"),
+            (12, "12th"),
+            (13, "13th"),
+            (22, "22nd"),
+        ];
+
+        for (n, ordinal_str) in expected {
+            assert_eq!(ordinal_str, ordinal(n));
+        }
+    }
+}
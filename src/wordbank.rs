@@ -1,29 +1,148 @@
 extern crate rand;
 
-use rand::{thread_rng, Rng};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use rand::{thread_rng, Rng, SeedableRng};
+use rand::seq::SliceRandom;
+use rand::rngs::StdRng;
+
+use crate::constants::{WORD_LENGTH, MIN_WORD_LENGTH, MAX_WORD_LENGTH};
+use crate::err::AppError;
+use crate::wordpack::{self, WordPack};
+
+// Which words the answer is picked from.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum WordList {
+    // The curated list of common words.
+    Common,
+    // Any word that is allowed as a guess.
+    Full,
+    // Lists read from files, one word per line. A missing list is the same as
+    // the other one, or the built-in list if neither is given.
+    Files { answers: Option<PathBuf>, allowed: Option<PathBuf> },
+    // A word pack, by name.
+    Pack(String),
+}
+
+impl WordList {
+    // Any other name is taken as the name of a word pack.
+    pub fn parse(name: &str) -> Option<WordList> {
+        match name.to_lowercase().as_str() {
+            "common" => Some(WordList::Common),
+            "full" => Some(WordList::Full),
+            name if wordpack::is_name_valid(name) => Some(WordList::Pack(name.to_string())),
+            _ => None,
+        }
+    }
+
+    pub fn get_names() -> Vec<&'static str> {
+        vec!["common", "full", "or the name of a word pack"]
+    }
+}
 
 pub struct WordBank {
+    word_length: usize,
     unknown_words: Vec<String>,
     allowed_words: Vec<String>,
 }
 
 impl WordBank {
     pub fn init() -> WordBank {
-        let unknown_words_str: &str = include_str!("unknown_words.txt");
+        WordBank::init_with_length(WORD_LENGTH)
+    }
+
+    // Only the five-letter lists have a separate set of allowed guesses; the
+    // lists for the other lengths double as their own allowed guesses.
+    pub fn init_with_length(word_length: usize) -> WordBank {
+        assert!((MIN_WORD_LENGTH..=MAX_WORD_LENGTH).contains(&word_length));
+
+        let (unknown_words_str, allowed_words_str): (&str, &str) = match word_length {
+            4 => (include_str!("words/words_4.txt"), include_str!("words/words_4.txt")),
+            5 => (include_str!("unknown_words.txt"), include_str!("allowed_words.txt")),
+            6 => (include_str!("words/words_6.txt"), include_str!("words/words_6.txt")),
+            7 => (include_str!("words/words_7.txt"), include_str!("words/words_7.txt")),
+            8 => (include_str!("words/words_8.txt"), include_str!("words/words_8.txt")),
+            _ => unreachable!(),
+        };
+
         let mut unknown_words: Vec<String> = Vec::new();
 
         for line in unknown_words_str.lines() {
             unknown_words.push(line.to_string());
         }
 
-        let allowed_words_str: &str = include_str!("allowed_words.txt");
         let mut allowed_words: Vec<String> = Vec::new();
 
         for line in allowed_words_str.lines() {
             allowed_words.push(line.to_string());
         }
 
-        WordBank { unknown_words, allowed_words }
+        WordBank { word_length, unknown_words, allowed_words }
+    }
+
+    // The answers of the pack can be guessed along with its extra guesses and
+    // the usual allowed words of the same length.
+    pub fn init_with_pack(pack: &WordPack) -> WordBank {
+        let word_length = pack.get_word_length();
+        let mut allowed_words = WordBank::init_with_length(word_length).allowed_words;
+
+        allowed_words.extend(pack.get_answers().iter().cloned());
+        allowed_words.extend(pack.get_extra_allowed().iter().cloned());
+        allowed_words.sort();
+        allowed_words.dedup();
+
+        WordBank { word_length, unknown_words: pack.get_answers().clone(), allowed_words }
+    }
+
+    pub fn init_with_list(word_length: usize, word_list: &WordList) -> Result<WordBank, AppError> {
+        let mut wordbank = WordBank::init_with_length(word_length);
+
+        match word_list {
+            WordList::Pack(name) => {
+                let pack = wordpack::find_pack(name)?;
+
+                if pack.get_word_length() != word_length {
+                    return Err(AppError::WordPackLengthErr(name.clone(), pack.get_word_length()));
+                }
+
+                wordbank = WordBank::init_with_pack(&pack);
+            }
+            WordList::Common => (),
+            WordList::Full => wordbank.unknown_words = wordbank.allowed_words.clone(),
+            WordList::Files { answers, allowed } => {
+                let answers = answers.as_deref().map(|path| read_word_list(path, word_length)).transpose()?;
+                let allowed = allowed.as_deref().map(|path| read_word_list(path, word_length)).transpose()?;
+
+                match (answers, allowed) {
+                    (Some(answers), None) => {
+                        wordbank.allowed_words = answers.clone();
+                        wordbank.unknown_words = answers;
+                    }
+                    (answers, allowed) => {
+                        wordbank.unknown_words = answers.unwrap_or(wordbank.unknown_words);
+                        wordbank.allowed_words = allowed.unwrap_or(wordbank.allowed_words);
+                    }
+                }
+
+                // Guessing the answer must always be possible.
+                let missing: Vec<String> = wordbank.unknown_words
+                    .iter()
+                    .filter(|word| !wordbank.in_allowed_words(word))
+                    .cloned()
+                    .collect();
+
+                if !missing.is_empty() {
+                    return Err(AppError::AnswersNotAllowedErr(missing));
+                }
+            }
+        }
+
+        Ok(wordbank)
+    }
+
+    pub fn get_word_length(&self) -> usize {
+        self.word_length
     }
 
     pub fn get_unknown_words(&self) -> &Vec<String> {
@@ -35,16 +154,45 @@ impl WordBank {
     }
 
     pub fn get_random_word_in_unknown_words(&self) -> String {
-        let mut rng = thread_rng();
+        self.get_word_with_rng(&mut thread_rng())
+    }
+
+    pub fn get_word_with_rng<R: Rng + ?Sized>(&self, rng: &mut R) -> String {
         let unknown_words_len = self.unknown_words.len();
 
         assert_ne!(0, unknown_words_len);
 
-        let rand_index = rng.gen_range(0..self.unknown_words.len());
+        let rand_index = rng.gen_range(0..unknown_words_len);
 
         self.unknown_words[rand_index].clone()
     }
 
+    // Picks `count` different words, e.g. one for each board of a multi-board game.
+    pub fn get_words_with_rng<R: Rng + ?Sized>(&self, rng: &mut R, count: usize) -> Vec<String> {
+        assert!(count <= self.unknown_words.len());
+
+        self.unknown_words
+            .choose_multiple(rng, count)
+            .cloned()
+            .collect()
+    }
+
+    // The same seed gives the same word for as long as the word list and the
+    // rand crate stay the same. Use puzzle IDs to share a word for good.
+    pub fn get_word_with_seed(&self, seed: u64) -> String {
+        self.get_word_with_rng(&mut StdRng::seed_from_u64(seed))
+    }
+
+    pub fn get_unknown_word(&self, index: usize) -> Option<&String> {
+        self.unknown_words.get(index)
+    }
+
+    pub fn get_unknown_word_index(&self, target_word: &str) -> Option<usize> {
+        self.unknown_words
+            .binary_search(&target_word.to_string())
+            .ok()
+    }
+
     pub fn in_unknown_words(&self, target_word: &str) -> bool {
         self.unknown_words
             .binary_search(&target_word.to_string())
@@ -58,6 +206,70 @@ impl WordBank {
     }
 }
 
+// Reads a word list file. The words are lower-cased, blank lines are skipped,
+// and every problem that would break the game is reported at once.
+fn read_word_list(path: &Path, word_length: usize) -> Result<Vec<String>, AppError> {
+    let path_str = path.display().to_string();
+    let text = fs::read_to_string(path)
+        .map_err(|err| AppError::WordListReadErr(path_str.clone(), err.to_string()))?;
+
+    parse_word_list(&text, word_length)
+        .map_err(|issues| AppError::InvalidWordListFileErr(path_str, issues))
+}
+
+fn parse_word_list(text: &str, word_length: usize) -> Result<Vec<String>, Vec<String>> {
+    parse_words(text.lines().enumerate().map(|(i, line)| (i + 1, line)), word_length)
+}
+
+// Checks numbered lines of words. The words must be sorted and unique, since
+// they are looked up with a binary search.
+pub(crate) fn parse_words<'a, I>(lines: I, word_length: usize) -> Result<Vec<String>, Vec<String>>
+where
+    I: Iterator<Item = (usize, &'a str)>,
+{
+    let mut words: Vec<String> = Vec::new();
+    let mut issues: Vec<String> = Vec::new();
+
+    for (line_number, line) in lines {
+        let word = line.trim().to_lowercase();
+
+        if word.is_empty() {
+            continue;
+        }
+
+        if !word.chars().all(|c| c.is_ascii_alphabetic()) {
+            issues.push(format!("line {}: '{}' has characters other than letters", line_number, word));
+            continue;
+        }
+
+        if word.len() != word_length {
+            issues.push(format!(
+                "line {}: '{}' has {} letters instead of {}", line_number, word, word.len(), word_length
+            ));
+            continue;
+        }
+
+        match words.last() {
+            Some(last) if *last == word => {
+                issues.push(format!("line {}: '{}' is a duplicate", line_number, word));
+                continue;
+            }
+            Some(last) if *last > word => issues.push(format!(
+                "line {}: '{}' comes after '{}', but the list must be sorted", line_number, word, last
+            )),
+            _ => (),
+        }
+
+        words.push(word);
+    }
+
+    if words.is_empty() && issues.is_empty() {
+        issues.push("the list has no words".to_string());
+    }
+
+    if issues.is_empty() { Ok(words) } else { Err(issues) }
+}
+
 
 #[cfg(test)]
 mod tests {
@@ -120,4 +332,149 @@ mod tests {
             assert!(!wordbank.in_allowed_words(word));
         }
     }
+
+    #[test]
+    fn test_get_word_with_seed() {
+        let wordbank = WordBank::init();
+
+        for seed in [0, 1, 42, u64::MAX] {
+            let word = wordbank.get_word_with_seed(seed);
+
+            assert!(wordbank.in_unknown_words(&word));
+            assert_eq!(word, wordbank.get_word_with_seed(seed));
+        }
+    }
+
+    #[test]
+    fn test_get_words_with_rng() {
+        let wordbank = WordBank::init_with_length(8);
+        let mut words = wordbank.get_words_with_rng(&mut StdRng::seed_from_u64(1), 8);
+
+        assert!(words.iter().all(|word| wordbank.in_unknown_words(word)));
+
+        words.sort();
+        words.dedup();
+        assert_eq!(8, words.len());
+    }
+
+    #[test]
+    fn test_get_unknown_word_index() {
+        let wordbank = WordBank::init();
+
+        assert_eq!(Some(0), wordbank.get_unknown_word_index("aback"));
+        assert_eq!(None, wordbank.get_unknown_word_index("zymic"));
+
+        let index = wordbank.get_unknown_word_index("linen").unwrap();
+        assert_eq!(Some(&"linen".to_string()), wordbank.get_unknown_word(index));
+    }
+
+    #[test]
+    fn test_init_with_list() {
+        let wordbank = WordBank::init_with_list(5, &WordList::Full).unwrap();
+
+        assert_eq!(wordbank.get_allowed_words(), wordbank.get_unknown_words());
+        assert!(wordbank.in_unknown_words("zymic"));
+
+        assert_eq!(Some(WordList::Common), WordList::parse("Common"));
+        assert_eq!(Some(WordList::Pack("tech".to_string())), WordList::parse("tech"));
+        assert_eq!(None, WordList::parse("../tech"));
+    }
+
+    #[test]
+    fn test_init_with_pack() {
+        let wordbank = WordBank::init_with_list(5, &WordList::Pack("food".to_string())).unwrap();
+
+        assert!(wordbank.in_unknown_words("pizza"));
+        assert!(!wordbank.in_unknown_words("hello"));
+        assert!(wordbank.in_allowed_words("hello"));
+        assert!(wordbank.in_allowed_words("gyoza"));
+        assert!(wordbank.get_allowed_words().windows(2).all(|w| w[0] < w[1]));
+
+        assert_eq!(
+            Some(AppError::WordPackLengthErr("space".to_string(), 6)),
+            WordBank::init_with_list(5, &WordList::Pack("space".to_string())).err()
+        );
+    }
+
+    #[test]
+    fn test_parse_word_list() {
+        assert_eq!(
+            Ok(vec!["hello".to_string(), "world".to_string()]),
+            parse_word_list("Hello\r\n\nworld\n", 5)
+        );
+
+        let issues = parse_word_list("hello\nhello\nh3llo\nhell\nabout\n", 5).unwrap_err();
+
+        assert_eq!(
+            vec![
+                "line 2: 'hello' is a duplicate",
+                "line 3: 'h3llo' has characters other than letters",
+                "line 4: 'hell' has 4 letters instead of 5",
+                "line 5: 'about' comes after 'hello', but the list must be sorted",
+            ],
+            issues
+        );
+
+        assert_eq!(Err(vec!["the list has no words".to_string()]), parse_word_list("\n", 5));
+    }
+
+    #[test]
+    fn test_init_with_list_files() {
+        let dir = std::env::temp_dir().join(format!("wordle_clone_test_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let answers = dir.join("answers.txt");
+        let allowed = dir.join("allowed.txt");
+        fs::write(&answers, "crate\nforge\n").unwrap();
+        fs::write(&allowed, "cargo\ncrate\nforge\n").unwrap();
+
+        let word_list = WordList::Files { answers: Some(answers.clone()), allowed: Some(allowed.clone()) };
+        let wordbank = WordBank::init_with_list(5, &word_list).unwrap();
+
+        assert_eq!(&vec!["crate".to_string(), "forge".to_string()], wordbank.get_unknown_words());
+        assert!(wordbank.in_allowed_words("cargo"));
+
+        // Without an allowed list, the answers are the only allowed guesses.
+        let word_list = WordList::Files { answers: Some(answers.clone()), allowed: None };
+        let wordbank = WordBank::init_with_list(5, &word_list).unwrap();
+
+        assert!(!wordbank.in_allowed_words("cargo"));
+
+        // The built-in answers are not in the custom allowed list.
+        let word_list = WordList::Files { answers: None, allowed: Some(allowed.clone()) };
+        assert!(matches!(
+            WordBank::init_with_list(5, &word_list), Err(AppError::AnswersNotAllowedErr(_))
+        ));
+
+        let word_list = WordList::Files { answers: Some(dir.join("missing.txt")), allowed: None };
+        assert!(matches!(
+            WordBank::init_with_list(5, &word_list), Err(AppError::WordListReadErr(_, _))
+        ));
+
+        let word_list = WordList::Files { answers: Some(answers), allowed: None };
+        assert!(matches!(
+            WordBank::init_with_list(6, &word_list), Err(AppError::InvalidWordListFileErr(_, _))
+        ));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_init_with_length() {
+        for word_length in MIN_WORD_LENGTH..=MAX_WORD_LENGTH {
+            let wordbank = WordBank::init_with_length(word_length);
+
+            assert_eq!(word_length, wordbank.get_word_length());
+            assert!(!wordbank.get_unknown_words().is_empty());
+
+            for words in [wordbank.get_unknown_words(), wordbank.get_allowed_words()] {
+                assert!(words.windows(2).all(|w| w[0] < w[1]));
+                assert!(words.iter().all(|w| w.len() == word_length));
+            }
+
+            for word in wordbank.get_unknown_words() {
+                assert!(wordbank.in_allowed_words(word));
+            }
+        }
+    }
 }
\ No newline at end of file
@@ -0,0 +1,282 @@
+// Themed word packs. A pack is a single text file with a name, a description,
+// the word length, the answers and some extra allowed guesses:
+//
+//     # Comments start with a hash.
+//     name = tech
+//     description = Programming and computing terms
+//     length = 5
+//
+//     [answers]
+//     array
+//     ...
+//
+//     [allowed]
+//     rustc
+//     ...
+//
+// A few packs come with the game, and more can be dropped in the "packs"
+// directory of the game's configuration.
+
+use std::fs;
+use std::path::PathBuf;
+
+use crate::constants::{MIN_WORD_LENGTH, MAX_WORD_LENGTH};
+use crate::err::AppError;
+use crate::utils;
+use crate::wordbank;
+
+const BUNDLED_PACKS: [&str; 3] = [
+    include_str!("packs/food.txt"),
+    include_str!("packs/space.txt"),
+    include_str!("packs/tech.txt"),
+];
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct WordPack {
+    name: String,
+    description: String,
+    word_length: usize,
+    answers: Vec<String>,
+    extra_allowed: Vec<String>,
+}
+
+impl WordPack {
+    // Reports every problem with the pack at once, like word list files.
+    pub fn parse(text: &str) -> Result<WordPack, Vec<String>> {
+        let mut fields: Vec<(String, String)> = Vec::new();
+        let mut answer_lines: Vec<(usize, &str)> = Vec::new();
+        let mut allowed_lines: Vec<(usize, &str)> = Vec::new();
+        let mut issues: Vec<String> = Vec::new();
+
+        let mut section: Option<&str> = None;
+
+        for (i, line) in text.lines().enumerate() {
+            let line_number = i + 1;
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            match (line, section) {
+                ("[answers]", _) => section = Some("answers"),
+                ("[allowed]", _) => section = Some("allowed"),
+                (_, Some("answers")) => answer_lines.push((line_number, line)),
+                (_, Some(_)) => allowed_lines.push((line_number, line)),
+                (_, None) => match line.split_once('=') {
+                    Some((key, value)) => fields.push((key.trim().to_string(), value.trim().to_string())),
+                    None => issues.push(format!("line {}: expected 'key = value', got '{}'", line_number, line)),
+                },
+            }
+        }
+
+        let get_field = |key: &str| fields.iter().find(|(k, _)| k == key).map(|(_, v)| v.clone());
+
+        let name = get_field("name").unwrap_or_default();
+        let description = get_field("description").unwrap_or_default();
+
+        if !is_name_valid(&name) {
+            issues.push(format!(
+                "the name must be made of lower-case letters, digits and dashes, got '{}'", name
+            ));
+        }
+
+        let word_length = match get_field("length").map(|len| len.parse::<usize>()) {
+            Some(Ok(len)) if (MIN_WORD_LENGTH..=MAX_WORD_LENGTH).contains(&len) => len,
+            _ => {
+                issues.push(format!(
+                    "the length must be a number from {} to {}", MIN_WORD_LENGTH, MAX_WORD_LENGTH
+                ));
+                return Err(issues);
+            }
+        };
+
+        let answers = wordbank::parse_words(answer_lines.into_iter(), word_length)
+            .unwrap_or_else(|answer_issues| {
+                issues.extend(answer_issues);
+                Vec::new()
+            });
+
+        let extra_allowed = if allowed_lines.is_empty() {
+            Vec::new()
+        } else {
+            wordbank::parse_words(allowed_lines.into_iter(), word_length)
+                .unwrap_or_else(|allowed_issues| {
+                    issues.extend(allowed_issues);
+                    Vec::new()
+                })
+        };
+
+        if !issues.is_empty() {
+            return Err(issues);
+        }
+
+        Ok(WordPack { name, description, word_length, answers, extra_allowed })
+    }
+
+    pub fn get_name(&self) -> &String {
+        &self.name
+    }
+
+    pub fn get_description(&self) -> &String {
+        &self.description
+    }
+
+    pub fn get_word_length(&self) -> usize {
+        self.word_length
+    }
+
+    pub fn get_answers(&self) -> &Vec<String> {
+        &self.answers
+    }
+
+    pub fn get_extra_allowed(&self) -> &Vec<String> {
+        &self.extra_allowed
+    }
+}
+
+// Pack names are used on the command line, so they are kept simple.
+pub fn is_name_valid(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
+
+pub fn get_packs_dir() -> PathBuf {
+    let mut packs_dir = utils::get_config_dir();
+
+    packs_dir.push("packs");
+    packs_dir
+}
+
+// Returns the bundled packs followed by the ones in the packs directory, by
+// name. A pack that cannot be read is left out, with the reason in the second
+// list. A pack in the directory replaces a bundled pack of the same name.
+pub fn load_packs() -> (Vec<WordPack>, Vec<AppError>) {
+    let mut packs: Vec<WordPack> = BUNDLED_PACKS
+        .iter()
+        .map(|text| WordPack::parse(text).expect("Bundled word packs should be valid."))
+        .collect();
+    let mut errors: Vec<AppError> = Vec::new();
+
+    for (_, pack) in read_pack_files() {
+        match pack {
+            Ok(pack) => {
+                packs.retain(|p| p.name != pack.name);
+                packs.push(pack);
+            }
+            Err(err) => errors.push(err),
+        }
+    }
+
+    packs.sort_by(|a, b| a.name.cmp(&b.name));
+
+    (packs, errors)
+}
+
+// The packs of the packs directory, in the order of their paths.
+fn read_pack_files() -> Vec<(PathBuf, Result<WordPack, AppError>)> {
+    let mut paths: Vec<PathBuf> = match fs::read_dir(get_packs_dir()) {
+        Ok(entries) => entries.filter_map(|entry| entry.ok().map(|entry| entry.path())).collect(),
+        Err(_) => Vec::new(),
+    };
+
+    paths.sort();
+    paths.retain(|path| path.extension().is_some_and(|ext| ext == "txt"));
+
+    paths
+        .into_iter()
+        .map(|path| {
+            let path_str = path.display().to_string();
+
+            let pack = fs::read_to_string(&path)
+                .map_err(|err| AppError::WordListReadErr(path_str.clone(), err.to_string()))
+                .and_then(|text| WordPack::parse(&text)
+                    .map_err(|issues| AppError::InvalidWordListFileErr(path_str, issues)));
+
+            (path, pack)
+        })
+        .collect()
+}
+
+pub fn find_pack(name: &str) -> Result<WordPack, AppError> {
+    let (packs, _) = load_packs();
+
+    if let Some(pack) = packs.iter().find(|pack| pack.name == name) {
+        return Ok(pack.clone());
+    }
+
+    // A broken pack file named after the pack is most likely the one that
+    // was asked for. Broken files of other packs do not matter here.
+    let broken_pack = read_pack_files()
+        .into_iter()
+        .find(|(path, pack)| pack.is_err() && path.file_stem().is_some_and(|stem| stem == name));
+
+    if let Some((_, Err(err))) = broken_pack {
+        return Err(err);
+    }
+
+    Err(AppError::UnknownWordPackErr(
+        name.to_string(), packs.iter().map(|pack| pack.name.clone()).collect()
+    ))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundled_packs() {
+        for text in BUNDLED_PACKS {
+            let pack = WordPack::parse(text).unwrap();
+
+            assert!(!pack.get_answers().is_empty());
+            assert!(!pack.get_description().is_empty());
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        let pack = WordPack::parse(
+            "# A test pack.\nname = test-1\ndescription = Just a test\nlength = 4\n\n\
+            [answers]\nbolt\nnuts\n\n[allowed]\ngear\n"
+        ).unwrap();
+
+        assert_eq!("test-1", pack.get_name());
+        assert_eq!("Just a test", pack.get_description());
+        assert_eq!(4, pack.get_word_length());
+        assert_eq!(&vec!["bolt".to_string(), "nuts".to_string()], pack.get_answers());
+        assert_eq!(&vec!["gear".to_string()], pack.get_extra_allowed());
+    }
+
+    #[test]
+    fn test_parse_issues() {
+        let issues = WordPack::parse("name = Tech\nlength = 5\noops\n[answers]\nbytes\nabc\n").unwrap_err();
+
+        assert_eq!(
+            vec![
+                "line 3: expected 'key = value', got 'oops'",
+                "the name must be made of lower-case letters, digits and dashes, got 'Tech'",
+                "line 6: 'abc' has 3 letters instead of 5",
+            ],
+            issues
+        );
+
+        assert_eq!(
+            Err(vec!["the length must be a number from 4 to 8".to_string()]),
+            WordPack::parse("name = tech\n[answers]\nbytes\n")
+        );
+        assert_eq!(
+            Err(vec!["the list has no words".to_string()]),
+            WordPack::parse("name = tech\nlength = 5\n")
+        );
+    }
+
+    #[test]
+    fn test_is_name_valid() {
+        assert!(is_name_valid("team-42"));
+
+        for name in ["", "Tech", "../tech", "my pack"] {
+            assert!(!is_name_valid(name));
+        }
+    }
+}